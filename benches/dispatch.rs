@@ -0,0 +1,72 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use route_lint::bench::{dispatch_rules, get_filtered_rules};
+use route_lint::{Config, Context};
+
+/**
+ * 生成一个包含 `count` 条路由的路由文件，每 10 条路由嵌套在一个父路由下
+ */
+fn generate_routes(count: usize) -> String {
+    let mut source = String::from("export default [\n");
+    for group in 0..count / 10 {
+        source.push_str(&format!(
+            "  {{\n    path: '/group-{}',\n    name: 'group{}',\n    access: 'canRead',\n    routes: [\n",
+            group, group
+        ));
+        for index in 0..9 {
+            source.push_str(&format!(
+                "      {{ path: '/group-{0}/page-{1}', name: 'page{1}', component: './Group{0}/Page{1}' }},\n",
+                group, index
+            ));
+        }
+        source.push_str("    ],\n  },\n");
+    }
+    source.push_str("  { component: './404' },\n];\n");
+    source
+}
+
+fn bench_dispatch(c: &mut Criterion) {
+    let config = Config::default();
+    let rules = get_filtered_rules(&config);
+    let mut group = c.benchmark_group("dispatch");
+    group.sample_size(10);
+
+    for count in [1_000, 10_000] {
+        let ast = route_lint::parse_program("routes.ts", generate_routes(count)).unwrap();
+        ast.with_view(|program| {
+            // 每个规则单独遍历一次语法树
+            group.bench_with_input(BenchmarkId::new("per_rule", count), &count, |b, _| {
+                b.iter(|| {
+                    let mut context = Context::new(
+                        "routes.ts".to_string(),
+                        ast.media_type(),
+                        &config,
+                        ast.source(),
+                        program,
+                    );
+                    for rule in &rules {
+                        rule.lint_program_with_ast_view(&mut context, program);
+                    }
+                    context.diagnostics().len()
+                })
+            });
+            // 所有规则共用一次遍历，不包括自定义规则，两边执行的是同一组规则
+            group.bench_with_input(BenchmarkId::new("single_pass", count), &count, |b, _| {
+                b.iter(|| {
+                    let mut context = Context::new(
+                        "routes.ts".to_string(),
+                        ast.media_type(),
+                        &config,
+                        ast.source(),
+                        program,
+                    );
+                    dispatch_rules(&mut context, program, &rules);
+                    context.diagnostics().len()
+                })
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_dispatch);
+criterion_main!(benches);
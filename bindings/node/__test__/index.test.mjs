@@ -0,0 +1,63 @@
+// 需要先执行 `npm run build:debug` 生成 index.js 和 .node 文件
+import assert from 'node:assert/strict';
+import fs from 'node:fs';
+import { createRequire } from 'node:module';
+import os from 'node:os';
+import path from 'node:path';
+import test from 'node:test';
+
+const require = createRequire(import.meta.url);
+const { lint, lintPath, fix } = require('../index.js');
+
+test('lint 返回 camelCase 的 LintDiagnostic', () => {
+  const code = "export default [{ path: '/a', children: [] }, { component: './404' }];";
+  const diagnostics = lint(code, { filename: 'routes.ts', config: {} });
+  const diagnostic = diagnostics.find((item) => item.code === 'no-use-children');
+  assert.ok(diagnostic);
+  assert.equal(diagnostic.filename, 'routes.ts');
+  assert.equal(diagnostic.severity, 'error');
+  assert.equal(diagnostic.range.start.line, 1);
+  assert.equal(diagnostic.fixes[0].changes[0].newText, 'routes');
+});
+
+test('fix 返回修复后的源码并保留 BOM', () => {
+  const code = "\uFEFFexport default [{ path: '/a', children: [] }, { component: './404' }];";
+  assert.equal(
+    fix(code, { config: {} }),
+    "\uFEFFexport default [{ path: '/a', routes: [] }, { component: './404' }];",
+  );
+});
+
+test('lintPath 会检查 import 进来的路由文件', () => {
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'route-lint-'));
+  fs.writeFileSync(
+    path.join(dir, 'config.ts'),
+    "import { defineConfig } from 'umi';\nimport routes from './routes';\nexport default defineConfig({ routes });\n",
+  );
+  fs.writeFileSync(
+    path.join(dir, 'routes.ts'),
+    "export default [{ path: '/a', component: './A' }, { path: '/a', component: './A' }, { component: './404' }];\n",
+  );
+
+  const results = lintPath(path.join(dir, 'config.ts'), { config: {} });
+  assert.deepEqual(
+    results.map((result) => path.basename(result.filename)),
+    ['config.ts', 'routes.ts'],
+  );
+  assert.deepEqual(
+    results[1].diagnostics.map((diagnostic) => diagnostic.code),
+    ['no-repeat-path'],
+  );
+  assert.deepEqual(results[1].warnings, []);
+});
+
+test('配置对象中插件的相对路径相对于当前工作目录', () => {
+  const diagnostics = lint('export default [];', { config: { plugins: ['missing.wasm'] } });
+  const error = diagnostics.find((diagnostic) => diagnostic.code === 'plugin-error');
+  assert.ok(error.message.includes(path.join(process.cwd(), 'missing.wasm')));
+});
+
+test('配置不合法时抛出异常', () => {
+  assert.throws(() => lint('export default [];', { config: { framework: 1 } }), /解析配置异常/);
+  assert.throws(() => lint('export default [', {}), /解析文件异常/);
+});
@@ -0,0 +1,111 @@
+//! route-lint 的 Node.js 绑定，umi 插件等 JavaScript 工具可以在进程内检查路由文件
+
+use napi::{Env, Error, JsUnknown, Result};
+use napi_derive::napi;
+use route_lint::{media_type_of, Config, LintDiagnostic, Linter};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// 没有传文件名时按照 TypeScript 的路由文件处理
+const DEFAULT_FILE_NAME: &str = "routes.ts";
+
+#[napi(object)]
+pub struct LintOptions {
+    /// 用于展示错误、查找配置文件和判断文件类型
+    pub filename: Option<String>,
+    /// 配置对象，和 .routelintrc.json 的内容一致，也可以是配置文件的路径
+    /// 不传时从文件所在目录向上查找配置文件
+    pub config: Option<Value>,
+}
+
+/**
+ * 检查源码，返回 `LintDiagnostic[]`
+ */
+#[napi]
+pub fn lint(env: Env, code: String, options: Option<LintOptions>) -> Result<JsUnknown> {
+    let (file_name, linter) = linter(options)?;
+    let result = linter
+        .lint_source(&file_name, code, media_type_of(&file_name))
+        .map_err(Error::from_reason)?;
+    env.to_js_value(&result.diagnostics)
+}
+
+/// `lintPath` 返回的一个文件的检查结果
+#[derive(Serialize)]
+struct FileResult {
+    filename: String,
+    diagnostics: Vec<LintDiagnostic>,
+    /// 无法找到的路由文件
+    warnings: Vec<String>,
+}
+
+/**
+ * 读取并检查文件，和命令行一样会检查 import 进来的路由文件，返回 `FileResult[]`
+ * 配置只能通过 `config` 传入或者查找，`filename` 会被忽略
+ */
+#[napi]
+pub fn lint_path(env: Env, path: String, options: Option<LintOptions>) -> Result<JsUnknown> {
+    let options = LintOptions {
+        filename: Some(path.clone()),
+        config: options.and_then(|options| options.config),
+    };
+    let (_, linter) = linter(Some(options))?;
+    let results: Vec<FileResult> = linter
+        .lint_path(Path::new(&path))
+        .map_err(Error::from_reason)?
+        .into_iter()
+        .map(|(path, report)| FileResult {
+            filename: path.display().to_string(),
+            diagnostics: report.diagnostics,
+            warnings: report.warnings,
+        })
+        .collect();
+    env.to_js_value(&results)
+}
+
+/**
+ * 应用所有可以自动修复的问题，返回修复后的源码
+ */
+#[napi]
+pub fn fix(code: String, options: Option<LintOptions>) -> Result<String> {
+    let (file_name, linter) = linter(options)?;
+    // 解析时会去掉 BOM，返回时需要保留
+    let bom = if code.starts_with('\u{feff}') {
+        "\u{feff}"
+    } else {
+        ""
+    };
+    let result = linter
+        .fix_source(&file_name, code, media_type_of(&file_name))
+        .map_err(Error::from_reason)?;
+    Ok(format!(
+        "{}{}",
+        bom,
+        result.parsed_source.source().text_str()
+    ))
+}
+
+fn linter(options: Option<LintOptions>) -> Result<(String, Linter)> {
+    let (file_name, config) = match options {
+        Some(options) => (options.filename, options.config),
+        None => (None, None),
+    };
+    let file_name = file_name.unwrap_or_else(|| DEFAULT_FILE_NAME.to_string());
+    let config = match config {
+        Some(Value::String(path)) => Config::load(Path::new(&path)).map_err(Error::from_reason)?,
+        // 配置对象中插件的相对路径相对于当前的工作目录
+        Some(value) => {
+            let config: Config = serde_json::from_value(value)
+                .map_err(|err| Error::from_reason(format!("解析配置异常： {}", err)))?;
+            let cwd = std::env::current_dir()
+                .map_err(|err| Error::from_reason(format!("获取工作目录异常： {}", err)))?;
+            config.resolve_plugins(&cwd)
+        }
+        None => match Config::discover(Path::new(&file_name)) {
+            Some(path) => Config::load(&path).map_err(Error::from_reason)?,
+            None => Config::default(),
+        },
+    };
+    Ok((file_name, Linter::new(config)))
+}
@@ -0,0 +1,116 @@
+//! route-lint 的 WebAssembly 版本，给文档中的路由配置 playground 使用
+//!
+//! ```bash
+//! cargo build --release --target wasm32-unknown-unknown -p route-lint-wasm
+//! wasm-bindgen --target web --out-dir bindings/wasm/pkg \
+//!     target/wasm32-unknown-unknown/release/route_lint_wasm.wasm
+//! ```
+
+use route_lint::{media_type_of, route_info, Config, LintDiagnostic, Linter, RouteInfo};
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+/// 没有传文件名时按照 TypeScript 的路由文件处理
+const DEFAULT_FILE_NAME: &str = "routes.ts";
+
+#[derive(Serialize)]
+pub struct LintOutput {
+    /// 按照规则顺序排列的错误
+    pub diagnostics: Vec<LintDiagnostic>,
+    /// 源码中解析出来的所有顶层路由
+    pub routes: Vec<RouteInfo>,
+}
+
+/**
+ * 检查源码，返回 `{ diagnostics, routes }` 格式的 JSON
+ * `config` 是 .routelintrc.json 格式的 JSON，配置中的插件会被忽略
+ */
+#[wasm_bindgen]
+pub fn lint(
+    code: String,
+    file_name: Option<String>,
+    config: Option<String>,
+) -> Result<String, JsError> {
+    lint_json(code, file_name, config).map_err(|err| JsError::new(&err))
+}
+
+pub fn lint_json(
+    code: String,
+    file_name: Option<String>,
+    config: Option<String>,
+) -> Result<String, String> {
+    let file_name = file_name.unwrap_or_else(|| DEFAULT_FILE_NAME.to_string());
+    let config: Config = match config {
+        Some(config) => {
+            serde_json::from_str(&config).map_err(|err| format!("解析配置异常： {}", err))?
+        }
+        None => Config::default(),
+    };
+    let linter = Linter::new(config);
+
+    let result = linter.lint_source(&file_name, code, media_type_of(&file_name))?;
+    let routes = route_info(&result.parsed_source, linter.config().framework);
+
+    let output = LintOutput {
+        diagnostics: result.diagnostics,
+        routes,
+    };
+    serde_json::to_string(&output).map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn returns_diagnostics_and_routes() {
+        let code =
+            "export default [{ path: '/a', routes: [{ path: 'b', name: 'B' }] }, { path: '/a' }];";
+        let output: Value =
+            serde_json::from_str(&lint_json(code.to_string(), None, None).unwrap()).unwrap();
+
+        let codes: Vec<&str> = output["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|diagnostic| diagnostic["code"].as_str().unwrap())
+            .collect();
+        assert!(codes.contains(&"no-repeat-path"));
+
+        let child = &output["routes"][0]["routes"][0];
+        assert_eq!(child["fullPath"], "/a/b");
+        assert_eq!(child["depth"], 1);
+        assert_eq!(child["fields"]["name"]["value"], "B");
+        assert_eq!(child["fields"]["name"]["text"], "'B'");
+        assert_eq!(child["range"]["start"]["line"], 1);
+    }
+
+    #[test]
+    fn serializes_fixes_in_camel_case() {
+        let code = "export default [{ path: '/a', children: [] }, { component: './404' }];";
+        let output: Value =
+            serde_json::from_str(&lint_json(code.to_string(), None, None).unwrap()).unwrap();
+
+        let diagnostic = output["diagnostics"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|diagnostic| diagnostic["code"] == "no-use-children")
+            .unwrap();
+        let change = &diagnostic["fixes"][0]["changes"][0];
+        assert_eq!(change["newText"], "routes");
+        assert_eq!(change["range"]["start"]["bytePos"], 30);
+    }
+
+    #[test]
+    fn reports_invalid_config_and_source() {
+        let config = Some(r#"{ "framework": 1 }"#.to_string());
+        assert!(lint_json("export default [];".to_string(), None, config)
+            .unwrap_err()
+            .starts_with("解析配置异常"));
+        assert!(lint_json("export default [".to_string(), None, None)
+            .unwrap_err()
+            .starts_with("解析文件异常"));
+    }
+}
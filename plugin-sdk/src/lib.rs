@@ -0,0 +1,262 @@
+//! 编写 route-lint WebAssembly 插件规则的 SDK
+//!
+//! 插件编译为 `wasm32-unknown-unknown` 的 cdylib，实现 [`Rule`] 并通过 [`export_rule!`]
+//! 导出。route-lint 会把解析好的路由树和源码传给插件，插件返回需要报告的问题和自动修复。
+//!
+//! ```ignore
+//! struct HideInMenu;
+//!
+//! impl route_lint_plugin::Rule for HideInMenu {
+//!     const CODE: &'static str = "settings-hide-in-menu";
+//!
+//!     fn check(input: &LintInput) -> Vec<Diagnostic> {
+//!         // ...
+//!     }
+//! }
+//!
+//! route_lint_plugin::export_rule!(HideInMenu);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub use serde_json::{self, Value};
+
+/// 插件和 route-lint 之间协议的版本，不兼容的修改需要增加版本
+pub const ABI_VERSION: u32 = 1;
+
+/// 源码中的一段范围，使用 UTF-8 字节偏移
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// 传给插件的内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintInput {
+    pub file_name: String,
+    /// 文件的源码，`Span` 都是相对于它的偏移
+    pub source: String,
+    /// 文件中所有的顶层路由
+    pub routes: Vec<Route>,
+}
+
+/// 从源码中解析出来的一个路由
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Route {
+    /// 配置里写的 path，没有配置或者不是字符串时为 None
+    pub path: Option<String>,
+    /// 拼接上父路由之后的完整路径
+    pub full_path: String,
+    /// 路由所在的层级，顶层为 0
+    pub depth: usize,
+    /// 路由对象的范围
+    pub span: Span,
+    /// 路由上配置的属性，vue-router 的 meta 中的属性也会放在这里
+    pub fields: BTreeMap<String, Field>,
+    pub routes: Vec<Route>,
+}
+
+/// 路由上的一个属性
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Field {
+    /// 字面量的值，变量、函数调用之类无法静态确定的值为 None
+    pub value: Option<Value>,
+    /// 属性值的源码
+    pub text: String,
+    /// 属性值的范围
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// 插件返回的一个问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub span: Span,
+    pub message: String,
+    #[serde(default)]
+    pub hint: Option<String>,
+    /// 不设置时为 error
+    #[serde(default)]
+    pub severity: Option<Severity>,
+    #[serde(default)]
+    pub fixes: Vec<Fix>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fix {
+    pub description: String,
+    pub changes: Vec<Change>,
+}
+
+/// 把 `span` 范围内的源码替换为 `new_text`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Change {
+    pub span: Span,
+    pub new_text: String,
+}
+
+/// 插件的基本信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Metadata {
+    pub code: String,
+}
+
+impl Route {
+    /// 包括自己在内，深度优先遍历所有的路由
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a Route)) {
+        visit(self);
+        for route in &self.routes {
+            route.walk(visit);
+        }
+    }
+
+    pub fn field(&self, key: &str) -> Option<&Field> {
+        self.fields.get(key)
+    }
+}
+
+impl LintInput {
+    /// 深度优先遍历所有的路由
+    pub fn walk<'a>(&'a self, visit: &mut impl FnMut(&'a Route)) {
+        for route in &self.routes {
+            route.walk(visit);
+        }
+    }
+}
+
+impl Field {
+    pub fn as_str(&self) -> Option<&str> {
+        self.value.as_ref().and_then(Value::as_str)
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        self.value.as_ref().and_then(Value::as_bool)
+    }
+}
+
+impl Diagnostic {
+    pub fn new(span: Span, message: impl ToString) -> Diagnostic {
+        Diagnostic {
+            span,
+            message: message.to_string(),
+            hint: None,
+            severity: None,
+            fixes: vec![],
+        }
+    }
+
+    pub fn with_hint(mut self, hint: impl ToString) -> Diagnostic {
+        self.hint = Some(hint.to_string());
+        self
+    }
+
+    pub fn with_severity(mut self, severity: Severity) -> Diagnostic {
+        self.severity = Some(severity);
+        self
+    }
+
+    pub fn with_fix(mut self, description: impl ToString, changes: Vec<Change>) -> Diagnostic {
+        self.fixes.push(Fix {
+            description: description.to_string(),
+            changes,
+        });
+        self
+    }
+}
+
+/// 插件规则
+pub trait Rule {
+    /// 规则的名字，展示在错误中，也可以在 rules.exclude 中使用
+    const CODE: &'static str;
+
+    fn check(input: &LintInput) -> Vec<Diagnostic>;
+}
+
+/**
+ * 执行规则并序列化结果，`export_rule!` 生成的导出函数会调用它
+ * 输入无法解析时 panic，插件中的 panic 会被 route-lint 当作执行异常
+ */
+#[doc(hidden)]
+pub fn run<R: Rule>(input: &[u8]) -> Vec<u8> {
+    let input: LintInput = serde_json::from_slice(input).expect("invalid lint input");
+    serde_json::to_vec(&R::check(&input)).expect("failed to serialize diagnostics")
+}
+
+#[doc(hidden)]
+pub fn metadata<R: Rule>() -> Vec<u8> {
+    let metadata = Metadata {
+        code: R::CODE.to_string(),
+    };
+    serde_json::to_vec(&metadata).expect("failed to serialize metadata")
+}
+
+/// 把指针和长度打包为一个 u64 返回给 route-lint
+#[doc(hidden)]
+pub fn into_raw(bytes: Vec<u8>) -> u64 {
+    let bytes = bytes.into_boxed_slice();
+    let len = bytes.len() as u64;
+    let ptr = Box::into_raw(bytes) as *mut u8 as usize as u64;
+    (ptr << 32) | len
+}
+
+/**
+ * 导出 route-lint 需要的函数：
+ *
+ * - `route_lint_abi_version() -> u32`
+ * - `route_lint_alloc(len: u32) -> u32`：route-lint 写入输入之前申请内存
+ * - `route_lint_dealloc(ptr: u32, len: u32)`：route-lint 读取结果之后释放内存
+ * - `route_lint_metadata() -> u64`：JSON 格式的 [`Metadata`]
+ * - `route_lint_check(ptr: u32, len: u32) -> u64`：输入是 JSON 格式的 [`LintInput`]，返回 JSON 格式的 [`Diagnostic`] 数组
+ *
+ * 返回的 u64 高 32 位是指针，低 32 位是长度
+ */
+#[macro_export]
+macro_rules! export_rule {
+    ($rule:ty) => {
+        #[no_mangle]
+        pub extern "C" fn route_lint_abi_version() -> u32 {
+            $crate::ABI_VERSION
+        }
+
+        #[no_mangle]
+        pub extern "C" fn route_lint_alloc(len: u32) -> u32 {
+            let buffer = vec![0u8; len as usize].into_boxed_slice();
+            Box::into_raw(buffer) as *mut u8 as usize as u32
+        }
+
+        /// # Safety
+        ///
+        /// `ptr` 和 `len` 必须来自 `route_lint_alloc` 或者返回给 route-lint 的结果
+        #[no_mangle]
+        pub unsafe extern "C" fn route_lint_dealloc(ptr: u32, len: u32) {
+            let slice = std::ptr::slice_from_raw_parts_mut(ptr as usize as *mut u8, len as usize);
+            drop(Box::from_raw(slice));
+        }
+
+        #[no_mangle]
+        pub extern "C" fn route_lint_metadata() -> u64 {
+            $crate::into_raw($crate::metadata::<$rule>())
+        }
+
+        /// # Safety
+        ///
+        /// `ptr` 和 `len` 必须来自 `route_lint_alloc`，调用之后这段内存由插件释放
+        #[no_mangle]
+        pub unsafe extern "C" fn route_lint_check(ptr: u32, len: u32) -> u64 {
+            let slice = std::ptr::slice_from_raw_parts_mut(ptr as usize as *mut u8, len as usize);
+            let input = Box::from_raw(slice);
+            $crate::into_raw($crate::run::<$rule>(&input))
+        }
+    };
+}
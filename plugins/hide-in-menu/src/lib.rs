@@ -0,0 +1,176 @@
+//! 示例插件：`/settings` 下面的路由都需要设置 `hideInMenu: true`
+//!
+//! ```bash
+//! cargo build --release --target wasm32-unknown-unknown -p route-lint-plugin-hide-in-menu
+//! ```
+
+use route_lint_plugin::{export_rule, Change, Diagnostic, LintInput, Rule, Severity, Span};
+
+/// 需要隐藏菜单的路径前缀
+const PREFIX: &str = "/settings/";
+
+pub struct HideInMenu;
+
+impl Rule for HideInMenu {
+    const CODE: &'static str = "settings-hide-in-menu";
+
+    fn check(input: &LintInput) -> Vec<Diagnostic> {
+        let mut diagnostics = vec![];
+        input.walk(&mut |route| {
+            if !route.full_path.starts_with(PREFIX) {
+                return;
+            }
+            match route.field("hideInMenu") {
+                // 变量之类无法确定的值不报错
+                Some(field) if field.value.is_none() || field.as_bool() == Some(true) => {}
+                Some(field) => diagnostics.push(
+                    Diagnostic::new(field.span, "🚨 /settings 下的路由需要隐藏菜单！")
+                        .with_severity(Severity::Warning)
+                        .with_fix(
+                            "改为 hideInMenu: true",
+                            vec![Change {
+                                span: field.span,
+                                new_text: "true".to_string(),
+                            }],
+                        ),
+                ),
+                None => {
+                    // 插入到 `{` 后面
+                    let brace = Span {
+                        start: route.span.start + 1,
+                        end: route.span.start + 1,
+                    };
+                    diagnostics.push(
+                        Diagnostic::new(route.span, "🚨 /settings 下的路由需要隐藏菜单！")
+                            .with_severity(Severity::Warning)
+                            .with_hint(format!("路由 `{}` 没有设置 hideInMenu", route.full_path))
+                            .with_fix(
+                                "添加 hideInMenu: true",
+                                vec![Change {
+                                    span: brace,
+                                    new_text: " hideInMenu: true,".to_string(),
+                                }],
+                            ),
+                    );
+                }
+            }
+        });
+        diagnostics
+    }
+}
+
+export_rule!(HideInMenu);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use route_lint_plugin::{Field, Route, Value};
+    use std::collections::BTreeMap;
+
+    fn route(full_path: &str, start: usize, fields: &[(&str, Option<Value>)]) -> Route {
+        let fields = fields
+            .iter()
+            .map(|(key, value)| {
+                let field = Field {
+                    value: value.clone(),
+                    text: String::new(),
+                    span: Span {
+                        start: 100,
+                        end: 104,
+                    },
+                };
+                (key.to_string(), field)
+            })
+            .collect::<BTreeMap<_, _>>();
+        Route {
+            path: Some(full_path.to_string()),
+            full_path: full_path.to_string(),
+            depth: 0,
+            span: Span {
+                start,
+                end: start + 10,
+            },
+            fields,
+            routes: vec![],
+        }
+    }
+
+    fn check(routes: Vec<Route>) -> Vec<Diagnostic> {
+        HideInMenu::check(&LintInput {
+            file_name: "routes.ts".to_string(),
+            source: String::new(),
+            routes,
+        })
+    }
+
+    #[test]
+    fn reports_missing_hide_in_menu() {
+        let mut settings = route("/settings", 0, &[]);
+        settings.routes.push(route("/settings/profile", 20, &[]));
+        let diagnostics = check(vec![settings]);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].span, Span { start: 20, end: 30 });
+        assert_eq!(diagnostics[0].severity, Some(Severity::Warning));
+        let change = &diagnostics[0].fixes[0].changes[0];
+        assert_eq!(change.span, Span { start: 21, end: 21 });
+        assert_eq!(change.new_text, " hideInMenu: true,");
+    }
+
+    #[test]
+    fn replaces_false_value() {
+        let diagnostics = check(vec![route(
+            "/settings/security",
+            0,
+            &[("hideInMenu", Some(Value::Bool(false)))],
+        )]);
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].span,
+            Span {
+                start: 100,
+                end: 104
+            }
+        );
+        assert_eq!(diagnostics[0].fixes[0].changes[0].new_text, "true");
+    }
+
+    #[test]
+    fn ignores_hidden_dynamic_and_other_routes() {
+        let diagnostics = check(vec![
+            route(
+                "/settings/profile",
+                0,
+                &[("hideInMenu", Some(Value::Bool(true)))],
+            ),
+            route("/settings/billing", 0, &[("hideInMenu", None)]),
+            route("/admin", 0, &[]),
+            route("/settings", 0, &[]),
+        ]);
+
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn serializes_through_the_exported_entry() {
+        let input = LintInput {
+            file_name: "routes.ts".to_string(),
+            source: String::new(),
+            routes: vec![route("/settings/profile", 0, &[])],
+        };
+        let output = route_lint_plugin::run::<HideInMenu>(&serde_json_bytes(&input));
+        let diagnostics: Vec<Diagnostic> =
+            route_lint_plugin::serde_json::from_slice(&output).unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "🚨 /settings 下的路由需要隐藏菜单！"
+        );
+    }
+
+    fn serde_json_bytes(input: &LintInput) -> Vec<u8> {
+        route_lint_plugin::serde_json::to_vec(input).unwrap()
+    }
+}
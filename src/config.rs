@@ -0,0 +1,163 @@
+use crate::diagnostic::Severity;
+use serde::{Deserialize, Deserializer};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// 默认的配置文件名，会从被检查文件所在目录开始向上查找
+pub const CONFIG_FILE_NAME: &str = ".routelintrc.json";
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    /// 路由配置所属的框架，决定了使用的规则和路由属性
+    pub framework: Framework,
+
+    /// 路由对象允许使用的属性
+    pub schema: SchemaConfig,
+
+    /// 开启或者关闭规则
+    pub rules: RulesConfig,
+
+    /// require-404 自动修复时追加的 404 路由
+    pub not_found_component: Option<String>,
+
+    /// no-repeat-name 检查重复 name 的范围
+    pub unique_name_scope: UniqueNameScope,
+
+    /// 项目自定义的规则，不需要编写 Rust 代码
+    pub custom_rules: Vec<CustomRuleConfig>,
+
+    /// .wasm 插件规则的路径，相对于配置文件所在的目录
+    pub plugins: Vec<PathBuf>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Framework {
+    /// umi / Ant Design Pro，使用 routes 配置子路由
+    #[default]
+    Umi,
+    /// react-router v6 的 createBrowserRouter / useRoutes
+    ReactRouter,
+    /// vue-router 的 createRouter
+    VueRouter,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UniqueNameScope {
+    /// 同一层的路由之间不能重复，否则菜单的 key 会冲突
+    #[default]
+    Siblings,
+    /// 整棵路由树都不能重复，否则国际化的 key 会冲突
+    Tree,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RulesConfig {
+    /// 额外开启的规则，比如默认关闭的 `require-404`
+    pub include: Vec<String>,
+    /// 需要关闭的规则
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SchemaConfig {
+    /// 在内置属性之外额外允许的 key，比如项目自定义的 `keepAlive`
+    pub extra_keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomRuleConfig {
+    /// 规则的名字，可以在 rules.exclude 中使用
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub hint: Option<String>,
+    #[serde(default)]
+    pub severity: Severity,
+    /// 需要检查的路由，不配置时检查所有路由
+    #[serde(default, rename = "match")]
+    pub matcher: RouteMatcher,
+    /// 匹配到的路由需要满足的条件，key 是路由属性
+    pub assert: BTreeMap<String, FieldCondition>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RouteMatcher {
+    /// 拼接之后的完整路径需要匹配其中一个 glob，`*` 匹配一段路径，`**` 匹配任意多段路径
+    #[serde(deserialize_with = "one_or_many")]
+    pub path: Vec<String>,
+    /// 路由属性需要满足的条件
+    pub fields: BTreeMap<String, FieldCondition>,
+}
+
+/// 对一个路由属性的要求，同时配置多个时需要全部满足
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FieldCondition {
+    /// 属性是否存在
+    pub exists: Option<bool>,
+    /// 属性的值，只支持字符串、布尔值和数字
+    pub equals: Option<serde_json::Value>,
+    /// 数组属性中需要包含的值
+    pub includes: Option<serde_json::Value>,
+    /// 字符串属性需要匹配的 glob
+    pub matches: Option<String>,
+}
+
+/// path 可以写一个字符串，也可以写一个数组
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(value) => vec![value],
+        OneOrMany::Many(values) => values,
+    })
+}
+
+impl Config {
+    pub fn not_found_component(&self) -> &str {
+        self.not_found_component.as_deref().unwrap_or("./404")
+    }
+
+    /**
+     * 读取并解析指定路径的配置文件
+     */
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("读取配置文件异常： `{}`: {}", path.display(), err))?;
+        let mut config: Config = serde_json::from_str(&content)
+            .map_err(|err| format!("解析配置文件异常： `{}`: {}", path.display(), err))?;
+        if let Some(dir) = path.parent() {
+            config = config.resolve_plugins(dir);
+        }
+        Ok(config)
+    }
+
+    /**
+     * 把插件的相对路径转化为相对于 `dir` 的路径，绝对路径保持不变
+     */
+    pub fn resolve_plugins(mut self, dir: &Path) -> Config {
+        self.plugins = self.plugins.iter().map(|plugin| dir.join(plugin)).collect();
+        self
+    }
+
+    /**
+     * 从 `file` 所在的目录开始向上查找配置文件
+     */
+    pub fn discover(file: &Path) -> Option<PathBuf> {
+        let dir = file.parent()?;
+        dir.ancestors()
+            .map(|dir| dir.join(CONFIG_FILE_NAME))
+            .find(|candidate| candidate.is_file())
+    }
+}
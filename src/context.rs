@@ -1,15 +1,20 @@
-﻿use deno_ast::{
+use deno_ast::{
     swc::common::{comments::Comment, BytePos, Span},
-    view::{RootNode, SourceFile},
+    view::{self as ast_view, RootNode, SourceFile},
     MediaType,
 };
 
-use crate::diagnostic::{LintDiagnostic, Position, Range};
+use crate::config::Config;
+use crate::diagnostic::{LintDiagnostic, LintFix, Position, Range, Severity};
+use crate::route::{is_route_object, RouteFrame};
 
 pub struct Context<'view> {
     /// File name on which the lint rule is run
     file_name: String,
 
+    /// The user configuration, e.g. the extra keys allowed in route objects
+    config: &'view Config,
+
     /// The media type which linter was configured with. Can be used
     /// to skip checking some rules.
     media_type: MediaType,
@@ -23,6 +28,12 @@ pub struct Context<'view> {
     /// The AST view of the program, which for example can be used for getting
     /// comments
     program: deno_ast::view::Program<'view>,
+
+    /// The ancestors of the node being visited, from the program down to its parent
+    ancestors: Vec<ast_view::Node<'view>>,
+
+    /// The routes enclosing the node being visited, from the top level route down
+    routes: Vec<RouteFrame<'view>>,
 }
 
 impl<'view> Context<'view> {
@@ -41,6 +52,19 @@ impl<'view> Context<'view> {
         self.diagnostics.push(diagnostic);
     }
 
+    pub fn add_diagnostic_with_fixes(
+        &mut self,
+        span: Span,
+        code: impl ToString,
+        message: impl ToString,
+        maybe_hint: Option<String>,
+        fixes: Vec<LintFix>,
+    ) {
+        let mut diagnostic = self.create_diagnostic(span, code, message, maybe_hint);
+        diagnostic.fixes = fixes;
+        self.diagnostics.push(diagnostic);
+    }
+
     pub fn all_comments(&self) -> impl Iterator<Item = &'view Comment> {
         self.program.comment_container().unwrap().all_comments()
     }
@@ -52,24 +76,99 @@ impl<'view> Context<'view> {
         message: impl ToString,
         maybe_hint: Option<String>,
     ) -> LintDiagnostic {
-        let start = Position::new(span.lo(), self.source_file.line_and_column_index(span.lo()));
-        let end = Position::new(span.hi(), self.source_file.line_and_column_index(span.hi()));
-
-        let diagnostic = LintDiagnostic {
-            range: Range { start, end },
+        LintDiagnostic {
+            range: self.range(span),
             filename: self.file_name.clone(),
             message: message.to_string(),
             code: code.to_string(),
             hint: maybe_hint,
-        };
+            severity: Severity::Error,
+            fixes: Vec::new(),
+        }
+    }
 
-        diagnostic
+    /// Adds a diagnostic created with `create_diagnostic`, e.g. by plugin rules
+    #[cfg(feature = "plugins")]
+    pub(crate) fn push_diagnostic(&mut self, diagnostic: LintDiagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Adds a diagnostic with the given severity, e.g. for custom rules
+    pub fn add_diagnostic_with_severity(
+        &mut self,
+        span: Span,
+        code: impl ToString,
+        message: impl ToString,
+        maybe_hint: Option<String>,
+        severity: Severity,
+    ) {
+        let mut diagnostic = self.create_diagnostic(span, code, message, maybe_hint);
+        diagnostic.severity = severity;
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Returns the ancestors of the node being visited, the parent comes last
+    pub fn ancestors(&self) -> &[ast_view::Node<'view>] {
+        &self.ancestors
+    }
+
+    /// Returns the parent of the node being visited
+    pub fn parent(&self) -> Option<ast_view::Node<'view>> {
+        self.ancestors.last().copied()
+    }
+
+    /// Returns the innermost route enclosing the node being visited. When
+    /// visiting a route object, this is the route itself.
+    pub fn current_route(&self) -> Option<&RouteFrame<'view>> {
+        self.routes.last()
+    }
+
+    /// Returns all routes enclosing the node being visited, the innermost comes last
+    pub fn routes(&self) -> &[RouteFrame<'view>] {
+        &self.routes
+    }
+
+    /// Called by the traversal before the handlers see `node`
+    pub(crate) fn enter_node(&mut self, node: ast_view::Node<'view>) {
+        if let ast_view::Node::ObjectLit(object_lit) = node {
+            if is_route_object(object_lit) {
+                let frame = RouteFrame::new(object_lit, self.routes.last());
+                self.routes.push(frame);
+            }
+        }
+    }
+
+    /// Called by the traversal before and after the children of `node` are visited
+    pub(crate) fn push_ancestor(&mut self, node: ast_view::Node<'view>) {
+        self.ancestors.push(node);
+    }
+
+    pub(crate) fn pop_ancestor(&mut self) {
+        self.ancestors.pop();
+    }
+
+    /// Called by the traversal after the handlers have left `node`
+    pub(crate) fn exit_node(&mut self, node: ast_view::Node<'view>) {
+        if let ast_view::Node::ObjectLit(object_lit) = node {
+            if matches!(self.routes.last(), Some(frame) if std::ptr::eq(frame.object, object_lit)) {
+                self.routes.pop();
+            }
+        }
+    }
+
+    pub fn config(&self) -> &'view Config {
+        self.config
     }
 
     pub fn diagnostics(&self) -> &[LintDiagnostic] {
         &self.diagnostics
     }
 
+    /// Stable sorts the diagnostics, e.g. by the order of the rules
+    pub fn sort_diagnostics_by_key<K: Ord>(&mut self, key: impl FnMut(&LintDiagnostic) -> K) {
+        self.diagnostics.sort_by_key(key);
+    }
+
     pub fn file_name(&self) -> &str {
         &self.file_name
     }
@@ -90,18 +189,22 @@ impl<'view> Context<'view> {
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub(crate) fn new(
+    pub fn new(
         file_name: String,
         media_type: MediaType,
+        config: &'view Config,
         source_file: &'view impl SourceFile,
         program: deno_ast::view::Program<'view>,
     ) -> Self {
         Self {
             file_name,
             media_type,
+            config,
             source_file,
             program,
             diagnostics: Vec::new(),
+            ancestors: Vec::new(),
+            routes: Vec::new(),
         }
     }
 
@@ -109,6 +212,13 @@ impl<'view> Context<'view> {
         &self.program
     }
 
+    /// Converts a span into a line and column based range, e.g. for fix changes
+    pub fn range(&self, span: Span) -> Range {
+        let start = Position::new(span.lo(), self.source_file.line_and_column_index(span.lo()));
+        let end = Position::new(span.hi(), self.source_file.line_and_column_index(span.hi()));
+        Range { start, end }
+    }
+
     pub fn source_file(&self) -> &dyn SourceFile {
         self.source_file
     }
@@ -120,3 +230,155 @@ impl<'view> Context<'view> {
             .trailing_comments(hi)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::{Control, Dispatcher, Handler};
+    use deno_ast::swc::common::Spanned;
+    use deno_ast::view::NodeTrait;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    const SOURCE: &str = "export default [
+  { path: '/a', access: 'canA', layout: false, routes: [
+    { path: 'b', routes: [{ path: 'c', component: './C' }] },
+    { path: '/a/d', access: 'canD', component: './D' },
+  ] },
+  { path: '/e', component: './E' },
+];";
+
+    type Seen = Rc<RefCell<Vec<String>>>;
+
+    /// Records what the handler sees when entering each route object
+    struct Recorder {
+        seen: Seen,
+        /// Skips the children of the route with this full path
+        skip: Option<&'static str>,
+    }
+
+    impl Handler for Recorder {
+        fn object_lit(&mut self, n: &ast_view::ObjectLit, ctx: &mut Context) -> Control {
+            let route = match ctx.current_route() {
+                Some(route) if std::ptr::eq(route.object, n) => route,
+                _ => return Control::Continue,
+            };
+            let text = |expr: Option<ast_view::Expr>| {
+                expr.map_or("-".to_string(), |expr| expr.text().to_string())
+            };
+            self.seen.borrow_mut().push(format!(
+                "{} depth={} access={} layout={} routes={} ancestors={}",
+                route.full_path,
+                route.depth,
+                text(route.access),
+                text(route.layout),
+                ctx.routes().len(),
+                ctx.ancestors().len(),
+            ));
+            match self.skip {
+                Some(skip) if route.full_path == skip => Control::SkipChildren,
+                _ => Control::Continue,
+            }
+        }
+    }
+
+    /// Records the kinds of the ancestors of the `./C` route
+    struct Chain(Seen);
+
+    impl Handler for Chain {
+        fn object_lit(&mut self, n: &ast_view::ObjectLit, ctx: &mut Context) -> Control {
+            if n.text() == "{ path: 'c', component: './C' }" {
+                let parent = ctx.parent().unwrap();
+                assert_eq!(parent.span(), n.parent().span());
+                let kinds = ctx
+                    .ancestors()
+                    .iter()
+                    .map(|node| format!("{:?}", node.kind()));
+                self.0.borrow_mut().extend(kinds);
+            }
+            Control::Continue
+        }
+    }
+
+    /**
+     * 用一组 handler 遍历 SOURCE，遍历结束之后两个栈都应该是空的
+     */
+    fn traverse(mut handlers: Vec<Box<dyn Handler>>) {
+        let config = Config::default();
+        let ast = crate::parse_program("routes.ts", SOURCE.to_string()).unwrap();
+        ast.with_view(|program| {
+            let mut context = Context::new(
+                "routes.ts".to_string(),
+                ast.media_type(),
+                &config,
+                ast.source(),
+                program,
+            );
+            let mut dispatcher = Dispatcher::default();
+            for handler in &mut handlers {
+                dispatcher.add(handler.as_mut());
+            }
+            dispatcher.traverse(program, &mut context);
+            assert!(context.ancestors().is_empty());
+            assert!(context.routes().is_empty());
+        });
+    }
+
+    /// 每个 handler 进入路由时看到的内容，`skips` 是每个 handler 跳过的路由
+    fn record(skips: &[Option<&'static str>]) -> Vec<Vec<String>> {
+        let seen: Vec<Seen> = skips.iter().map(|_| Seen::default()).collect();
+        let handlers = skips
+            .iter()
+            .zip(&seen)
+            .map(|(&skip, seen)| {
+                let seen = seen.clone();
+                Box::new(Recorder { seen, skip }) as Box<dyn Handler>
+            })
+            .collect();
+        traverse(handlers);
+        seen.into_iter().map(|seen| seen.take()).collect()
+    }
+
+    #[test]
+    fn ancestors_end_with_the_parent() {
+        let chain = Seen::default();
+        traverse(vec![Box::new(Chain(chain.clone()))]);
+        let route = ["ExprOrSpread", "ObjectLit", "KeyValueProp", "ArrayLit"];
+        let mut expected = vec!["Module", "ExportDefaultExpr", "ArrayLit"];
+        expected.extend(route);
+        expected.extend(route);
+        expected.push("ExprOrSpread");
+        assert_eq!(chain.take(), expected);
+    }
+
+    #[test]
+    fn current_route_resolves_paths_and_inherits_fields() {
+        assert_eq!(
+            record(&[None]),
+            [[
+                "/a depth=0 access='canA' layout=false routes=1 ancestors=4",
+                "/a/b depth=1 access='canA' layout=false routes=2 ancestors=8",
+                "/a/b/c depth=2 access='canA' layout=false routes=3 ancestors=12",
+                "/a/d depth=1 access='canD' layout=false routes=2 ancestors=8",
+                "/e depth=0 access=- layout=- routes=1 ancestors=4",
+            ]]
+        );
+    }
+
+    #[test]
+    fn stacks_are_popped_after_a_skipped_subtree() {
+        let all = record(&[None]).remove(0);
+        let skipped = record(&[Some("/a/b")]).remove(0);
+        let expected: Vec<_> = all
+            .iter()
+            .filter(|seen| !seen.starts_with("/a/b/"))
+            .cloned()
+            .collect();
+        assert_eq!(skipped, expected);
+
+        // 只有一个 handler 跳过时，其它 handler 仍然会遍历这个子树
+        let both = record(&[Some("/a"), None]);
+        assert_eq!(both[0], [all[0].as_str(), all[4].as_str()]);
+        assert_eq!(both[1], all);
+    }
+}
@@ -1,6 +1,6 @@
 use annotate_snippets::{display_list, snippet};
 use deno_ast::SourceTextInfo;
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Serialize, Serializer};
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -61,12 +61,38 @@ impl CharRange {
 }
 
 #[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFixChange {
+    pub new_text: String,
+    pub range: Range,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintFix {
+    pub description: String,
+    pub changes: Vec<LintFixChange>,
+}
+
+/// 问题的严重程度，内置规则都是 error，自定义规则可以配置为 warning
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct LintDiagnostic {
     pub code: String,
     pub filename: String,
     pub hint: Option<String>,
     pub message: String,
+    pub severity: Severity,
     pub range: Range,
+    pub fixes: Vec<LintFix>,
 }
 
 fn get_slice_source_and_range<'a>(
@@ -91,21 +117,36 @@ fn get_slice_source_and_range<'a>(
 pub fn display_diagnostics(diagnostics: &[LintDiagnostic], source_file: &SourceTextInfo) {
     for diagnostic in diagnostics {
         let (slice_source, char_range) = get_slice_source_and_range(source_file, &diagnostic.range);
-        let footer = if let Some(hint) = &diagnostic.hint {
-            vec![snippet::Annotation {
+        let mut footer = vec![];
+        if let Some(hint) = &diagnostic.hint {
+            footer.push(snippet::Annotation {
                 label: Some(hint),
                 id: None,
                 annotation_type: snippet::AnnotationType::Help,
-            }]
-        } else {
-            vec![]
-        };
+            });
+        }
+        let fix_labels: Vec<String> = diagnostic
+            .fixes
+            .iter()
+            .map(|fix| format!("💡 可以自动修复：{}", fix.description))
+            .collect();
+        for label in &fix_labels {
+            footer.push(snippet::Annotation {
+                label: Some(label),
+                id: None,
+                annotation_type: snippet::AnnotationType::Note,
+            });
+        }
 
+        let annotation_type = match diagnostic.severity {
+            Severity::Error => snippet::AnnotationType::Error,
+            Severity::Warning => snippet::AnnotationType::Warning,
+        };
         let snippet = snippet::Snippet {
             title: Some(snippet::Annotation {
                 label: Some(&diagnostic.message),
                 id: Some(&diagnostic.code),
-                annotation_type: snippet::AnnotationType::Error,
+                annotation_type,
             }),
             footer,
             slices: vec![snippet::Slice {
@@ -116,7 +157,7 @@ pub fn display_diagnostics(diagnostics: &[LintDiagnostic], source_file: &SourceT
                 annotations: vec![snippet::SourceAnnotation {
                     range: char_range.as_tuple(),
                     label: "",
-                    annotation_type: snippet::AnnotationType::Error,
+                    annotation_type,
                 }],
             }],
             opt: display_list::FormatOptions {
@@ -0,0 +1,389 @@
+use crate::diagnostic::LintDiagnostic;
+use crate::fix::unified_diff;
+use crate::{media_type_of, migrate, parallel, route, LintResult, Linter};
+use deno_ast::SourceTextInfo;
+use std::path::{Component, Path, PathBuf};
+
+/// 检查时如何处理自动修复
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FixMode {
+    /// 只检查，不修改文件
+    #[default]
+    Off,
+    /// 自动修复可以修复的问题，并写回文件
+    Write,
+    /// 只生成自动修复会产生的 diff，不修改文件
+    DryRun,
+}
+
+/// 需要检查的文件，import 进来的路由文件使用入口文件的配置
+pub struct Job<'a> {
+    pub path: PathBuf,
+    /// 标准输入之类已经读取好的内容，为空时读取文件
+    pub content: Option<String>,
+    pub linter: &'a Linter,
+}
+
+/// 一个文件的检查结果
+pub struct FileReport {
+    pub source: SourceTextInfo,
+    /// 按照规则顺序排列的错误，`FixMode::DryRun` 时为空
+    pub diagnostics: Vec<LintDiagnostic>,
+    /// 自动修复的 diff 或者提示
+    pub output: String,
+    /// 无法找到的路由文件
+    pub warnings: Vec<String>,
+    /// import 进来的路由文件
+    pub imports: Vec<PathBuf>,
+}
+
+/**
+ * 检查单个文件的内容，`FixMode::Write` 会先修复再返回剩下的问题，`FixMode::DryRun` 只返回 diff
+ * 返回检查的结果和需要打印的 diff 或者修复提示
+ */
+fn lint_file(
+    path: &Path,
+    content: String,
+    linter: &Linter,
+    fix: FixMode,
+) -> Result<(LintResult, String), String> {
+    // display 可以转化成需要显示的文案
+    let path_str: String = path.display().to_string();
+    let media_type = media_type_of(&path_str);
+
+    if fix == FixMode::Off {
+        let result = linter.lint_source(&path_str, content, media_type)?;
+        return Ok((result, String::new()));
+    }
+
+    let result = linter.fix_source(&path_str, content.clone(), media_type)?;
+    // 解析时会去掉 BOM，写回文件时需要保留
+    let bom = if content.starts_with('\u{feff}') {
+        "\u{feff}"
+    } else {
+        ""
+    };
+    let fixed = format!("{}{}", bom, result.parsed_source.source().text_str());
+    if fix == FixMode::DryRun {
+        let diff = unified_diff(&path_str, &content, &fixed);
+        // 引用的路由文件也需要打印 diff，这里不返回错误信息
+        let result = LintResult {
+            diagnostics: vec![],
+            ..result
+        };
+        return Ok((result, diff));
+    }
+    if fixed != content {
+        std::fs::write(path, fixed)
+            .map_err(|err| format!("写入文件异常： `{}`: {}", path_str, err))?;
+        return Ok((result, format!("🔧 已自动修复 `{}`\n", path_str)));
+    }
+    Ok((result, String::new()))
+}
+
+fn check_file(job: &Job, fix: FixMode) -> Result<FileReport, String> {
+    let path = &job.path;
+    // 读取文件内容
+    let content = match &job.content {
+        Some(content) => content.clone(),
+        None => std::fs::read_to_string(path)
+            .map_err(|err| format!("读取文件异常： `{}`: {}", path.display(), err))?,
+    };
+    let (result, output) = lint_file(path, content, job.linter, fix)?;
+
+    let mut imports = vec![];
+    let mut warnings = vec![];
+    for source in result.parsed_source.with_view(route::imported_routes) {
+        match resolve_import(path, &source) {
+            Some(resolved) => imports.push(resolved),
+            None => warnings.push(format!(
+                "⚠️ 无法找到 `{}` 中引用的路由文件 `{}`",
+                path.display(),
+                source
+            )),
+        }
+    }
+
+    Ok(FileReport {
+        source: result.parsed_source.source().clone(),
+        diagnostics: result.diagnostics,
+        output,
+        warnings,
+        imports,
+    })
+}
+
+/**
+ * 用 `jobs` 个线程检查入口文件，以及配置文件中 import 进来的路由文件
+ * 返回所有检查过的文件，按照文件路径排序，和检查完成的顺序无关
+ */
+pub fn lint_files(
+    entries: Vec<Job>,
+    fix: FixMode,
+    jobs: usize,
+) -> Vec<(PathBuf, Result<FileReport, String>)> {
+    // 记录已经检查过的文件，避免循环引用
+    let mut visited: Vec<PathBuf> = vec![];
+    let mut reports = vec![];
+    let mut pending = entries;
+    // 每一轮并行检查上一轮 import 进来的文件
+    while !pending.is_empty() {
+        let mut batch = vec![];
+        for job in pending {
+            if !visited.contains(&job.path) {
+                visited.push(job.path.clone());
+                batch.push(job);
+            }
+        }
+
+        let results = parallel::map(&batch, jobs, |job| check_file(job, fix));
+        pending = vec![];
+        for (job, result) in batch.iter().zip(results) {
+            if let Ok(report) = &result {
+                pending.extend(report.imports.iter().map(|path| Job {
+                    path: path.clone(),
+                    content: None,
+                    linter: job.linter,
+                }));
+            }
+            reports.push((job.path.clone(), result));
+        }
+    }
+
+    reports.sort_by(|(a, _), (b, _)| a.cmp(b));
+    reports
+}
+
+/**
+ * 按照相对路径找到 import 的文件，省略的后缀和 index 文件都会尝试
+ * `@/` 之类的别名依赖项目的构建配置，这里不处理
+ */
+fn resolve_import(from: &Path, source: &str) -> Option<PathBuf> {
+    if !source.starts_with('.') {
+        return None;
+    }
+    // 去掉路径中的 `./` 和 `../`，输出的文件名和直接检查时保持一致
+    let mut base = from.parent()?.to_path_buf();
+    for component in Path::new(source).components() {
+        match component {
+            Component::CurDir => {}
+            // 相对路径已经没有上一级目录可以去掉时，保留 `..`
+            Component::ParentDir if base.file_name().is_some() => {
+                base.pop();
+            }
+            component => base.push(component),
+        }
+    }
+    if base.is_file() {
+        return Some(base);
+    }
+    let name = base.file_name()?.to_str()?.to_string();
+    migrate::SOURCE_EXTENSIONS
+        .iter()
+        .map(|ext| base.with_file_name(format!("{}.{}", name, ext)))
+        .chain(
+            migrate::SOURCE_EXTENSIONS
+                .iter()
+                .map(|ext| base.join(format!("index.{}", ext))),
+        )
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    /// 在临时目录中写入 `files`，返回目录
+    fn project(files: &[(&str, &str)]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, content) in files {
+            let path = dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn resolves_extensions_and_index_files() {
+        let dir = project(&[
+            ("config/config.ts", ""),
+            ("config/routes.tsx", ""),
+            ("config/admin/index.js", ""),
+            ("src/user.ts", ""),
+        ]);
+        let from = dir.path().join("config/config.ts");
+        let resolve = |source| resolve_import(&from, source);
+        assert_eq!(
+            resolve("./routes"),
+            Some(dir.path().join("config/routes.tsx"))
+        );
+        assert_eq!(
+            resolve("./routes.tsx"),
+            Some(dir.path().join("config/routes.tsx"))
+        );
+        assert_eq!(
+            resolve("./admin"),
+            Some(dir.path().join("config/admin/index.js"))
+        );
+        assert_eq!(resolve("../src/user"), Some(dir.path().join("src/user.ts")));
+        assert_eq!(resolve("./missing"), None);
+        assert_eq!(resolve("@/routes"), None);
+    }
+
+    #[test]
+    fn follows_imports_sorted_by_path() {
+        let dir = project(&[
+            (
+                "config.ts",
+                "import { defineConfig } from 'umi';
+import routes from './routes';
+import { admin } from './missing';
+export default defineConfig({ routes: [...routes, ...admin] });",
+            ),
+            (
+                "routes/index.ts",
+                "export default [{ path: '/a', component: './A' }, { path: '/a', component: './A' }];",
+            ),
+        ]);
+        let linter = Linter::new(Config::default());
+        let entry = Job {
+            path: dir.path().join("config.ts"),
+            content: None,
+            linter: &linter,
+        };
+        let reports = lint_files(vec![entry], FixMode::Off, 2);
+
+        let paths: Vec<&Path> = reports.iter().map(|(path, _)| path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                dir.path().join("config.ts"),
+                dir.path().join("routes/index.ts")
+            ]
+        );
+        let config = reports[0].1.as_ref().unwrap();
+        assert_eq!(config.imports, [dir.path().join("routes/index.ts")]);
+        assert_eq!(config.warnings.len(), 1);
+        assert!(config.warnings[0].contains("./missing"));
+        let routes = reports[1].1.as_ref().unwrap();
+        assert!(routes
+            .diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.code == "no-repeat-path"));
+    }
+
+    #[test]
+    fn checks_each_file_once() {
+        // 两个入口文件 import 同一个路由文件，而且路由文件又 import 了入口文件
+        let config = "import routes from './routes';\nexport default { routes };";
+        let dir = project(&[
+            ("b.ts", config),
+            ("a.ts", config),
+            (
+                "routes.ts",
+                "import routes from './a';\nexport default { routes };",
+            ),
+        ]);
+        let linter = Linter::new(Config::default());
+        let entries = ["b.ts", "a.ts"]
+            .iter()
+            .map(|name| Job {
+                path: dir.path().join(name),
+                content: None,
+                linter: &linter,
+            })
+            .collect();
+        let paths: Vec<PathBuf> = lint_files(entries, FixMode::Off, 1)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(
+            paths,
+            ["a.ts", "b.ts", "routes.ts"].map(|name| dir.path().join(name))
+        );
+    }
+
+    /// 和命令行一样，从文件所在的目录开始查找配置
+    fn discover_linter(path: &Path) -> Linter {
+        let config = Config::discover(path)
+            .map(|config_path| Config::load(&config_path).unwrap())
+            .unwrap_or_default();
+        Linter::new(config)
+    }
+
+    fn codes(report: &FileReport) -> Vec<&str> {
+        report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn stdin_matches_file_on_disk() {
+        let source = "export default [{ path: '/a', children: [] }, { path: '/a', name: 'a' }];";
+        let dir = project(&[
+            (
+                "config/.routelintrc.json",
+                r#"{ "rules": { "exclude": ["no-use-children"] } }"#,
+            ),
+            ("config/routes.ts", source),
+        ]);
+        let path = dir.path().join("config/routes.ts");
+
+        let linter = discover_linter(&path);
+        let from_disk = Job {
+            path: path.clone(),
+            content: None,
+            linter: &linter,
+        };
+        let disk = lint_files(vec![from_disk], FixMode::Off, 1)
+            .remove(0)
+            .1
+            .unwrap();
+
+        // `--stdin --stdin-filename config/routes.ts`，配置同样从文件名所在的目录查找
+        let linter = discover_linter(&path);
+        let from_stdin = Job {
+            path: path.clone(),
+            content: Some(source.to_string()),
+            linter: &linter,
+        };
+        let stdin = lint_files(vec![from_stdin], FixMode::Off, 1)
+            .remove(0)
+            .1
+            .unwrap();
+
+        assert!(!codes(&disk).is_empty());
+        assert!(!codes(&disk).contains(&"no-use-children"));
+        assert_eq!(codes(&stdin), codes(&disk));
+        let ranges = |report: &FileReport| -> Vec<_> {
+            report
+                .diagnostics
+                .iter()
+                .map(|diagnostic| (diagnostic.filename.clone(), diagnostic.range.clone()))
+                .collect()
+        };
+        assert_eq!(ranges(&stdin), ranges(&disk));
+    }
+
+    #[test]
+    fn stdin_does_not_read_the_file() {
+        // 标准输入对应的文件可以不存在，比如编辑器中还没有保存的新文件
+        let dir = project(&[(
+            ".routelintrc.json",
+            r#"{ "rules": { "exclude": ["no-use-children"] } }"#,
+        )]);
+        let path = dir.path().join("new/routes.ts");
+        let linter = discover_linter(&path);
+        let job = Job {
+            path,
+            content: Some("export default [{ path: '/a', children: [] }];".to_string()),
+            linter: &linter,
+        };
+        let report = lint_files(vec![job], FixMode::Off, 1).remove(0).1.unwrap();
+        assert!(!codes(&report).contains(&"no-use-children"));
+    }
+}
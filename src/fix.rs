@@ -0,0 +1,324 @@
+use crate::context::Context;
+use crate::diagnostic::{LintDiagnostic, LintFixChange};
+use deno_ast::swc::common::{BytePos, Span, Spanned};
+use deno_ast::view as ast_view;
+
+/// 修复之后重新检查的最大次数，避免两个规则互相修改时死循环
+pub const MAX_FIX_PASSES: usize = 10;
+
+/**
+ * 应用每个错误的第一个修复方案，和已经应用的修改有重叠的修复会留到下一轮
+ * 返回修复后的文本和应用的修复数量
+ */
+pub fn apply_fixes(text: &str, diagnostics: &[LintDiagnostic]) -> (String, usize) {
+    let mut accepted: Vec<&LintFixChange> = vec![];
+    let mut applied = 0;
+
+    for fix in diagnostics
+        .iter()
+        .filter_map(|diagnostic| diagnostic.fixes.first())
+    {
+        let overlaps = fix.changes.iter().any(|change| {
+            accepted.iter().any(|other| {
+                (change.range.start.byte_pos < other.range.end.byte_pos
+                    && other.range.start.byte_pos < change.range.end.byte_pos)
+                    // 在同一个位置插入的两个修改也算作冲突
+                    || change.range.start.byte_pos == other.range.start.byte_pos
+            })
+        });
+        if overlaps {
+            continue;
+        }
+        accepted.extend(fix.changes.iter());
+        applied += 1;
+    }
+
+    (apply_changes(text, accepted), applied)
+}
+
+/**
+ * 把修改应用到文本上，调用方需要保证修改之间没有重叠
+ */
+pub fn apply_changes<'a>(
+    text: &str,
+    changes: impl IntoIterator<Item = &'a LintFixChange>,
+) -> String {
+    let mut changes: Vec<&LintFixChange> = changes.into_iter().collect();
+    // 从后往前替换，前面的位置就不会受影响；同一位置先删除再插入
+    changes.sort_by_key(|change| {
+        std::cmp::Reverse((change.range.start.byte_pos, change.range.end.byte_pos))
+    });
+    let mut fixed = text.to_string();
+    for change in changes {
+        fixed.replace_range(
+            change.range.start.byte_pos..change.range.end.byte_pos,
+            &change.new_text,
+        );
+    }
+    fixed
+}
+
+/**
+ * 生成删除对象中若干属性的修改，会连同属性之间的逗号一起删除
+ * 属于其它属性的注释会被保留
+ */
+pub fn remove_props(
+    object_lit: &ast_view::ObjectLit,
+    should_remove: impl Fn(&ast_view::PropOrSpread) -> bool,
+    ctx: &Context,
+) -> Vec<LintFixChange> {
+    let props = &object_lit.props;
+    let mut spans: Vec<Span> = vec![];
+
+    for (index, prop) in props.iter().enumerate() {
+        if !should_remove(prop) {
+            continue;
+        }
+        let span = match props.get(index + 1) {
+            // 删除到下一个属性开始的位置，下一个属性前面另起一行的注释需要保留
+            Some(next) => {
+                let end = ctx
+                    .leading_comments_at(next.span().lo)
+                    .map(|comment| comment.span.lo)
+                    .find(|lo| !is_same_line(prop.span().hi, *lo, ctx))
+                    .unwrap_or_else(|| next.span().lo);
+                Span::new(prop.span().lo, end, Default::default())
+            }
+            // 最后一个属性，从上一个保留的属性结尾开始删除，保留原来的尾逗号
+            None => match props[..index]
+                .iter()
+                .rev()
+                .find(|prev| !should_remove(prev))
+            {
+                Some(prev) => remove_last_prop(prev.span(), prop.span(), ctx),
+                None => prop.span(),
+            },
+        };
+        spans.push(span);
+    }
+
+    // 合并有重叠的区间，同一个修复中的修改不能重叠
+    let mut merged: Vec<Span> = vec![];
+    spans.sort_by_key(|span| span.lo);
+    for span in spans {
+        match merged.last_mut() {
+            Some(last) if span.lo <= last.hi => {
+                *last = Span::new(last.lo, std::cmp::max(last.hi, span.hi), Default::default());
+            }
+            _ => merged.push(span),
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|span| LintFixChange {
+            new_text: String::new(),
+            range: ctx.range(span),
+        })
+        .collect()
+}
+
+/// 两个位置之间没有换行
+fn is_same_line(lo: BytePos, hi: BytePos, ctx: &Context) -> bool {
+    !ctx.source_file().text()[lo.0 as usize..hi.0 as usize].contains('\n')
+}
+
+/**
+ * 和上一个属性在同一行的注释属于上一个属性，这时从注释结尾开始删除，并且连同这个属性的尾逗号一起删除
+ */
+fn remove_last_prop(prev: Span, prop: Span, ctx: &Context) -> Span {
+    let trailing_end = ctx
+        .all_comments()
+        .filter(|comment| comment.span.lo >= prev.hi && comment.span.hi <= prop.lo)
+        .filter(|comment| is_same_line(prev.hi, comment.span.lo, ctx))
+        .map(|comment| comment.span.hi)
+        .max();
+    let start = match trailing_end {
+        Some(start) => start,
+        None => return Span::new(prev.hi, prop.hi, Default::default()),
+    };
+
+    let rest = &ctx.source_file().text()[prop.hi.0 as usize..];
+    let after = rest.trim_start();
+    let end = if after.starts_with(',') {
+        prop.hi + BytePos((rest.len() - after.len() + 1) as u32)
+    } else {
+        prop.hi
+    };
+    Span::new(start, end, Default::default())
+}
+
+/**
+ * 生成修复前后的 unified diff
+ */
+pub fn unified_diff(file_name: &str, original: &str, fixed: &str) -> String {
+    let name = file_name.trim_start_matches("./").trim_start_matches('/');
+    similar::TextDiff::from_lines(original, fixed)
+        .unified_diff()
+        .context_radius(3)
+        .header(&format!("a/{}", name), &format!("b/{}", name))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::diagnostic::{LintFix, Position, Range};
+    use crate::route::prop_key;
+    use deno_ast::view::NodeTrait;
+
+    fn change(start: usize, end: usize, new_text: &str) -> LintFixChange {
+        let position = |byte_pos| Position {
+            line_index: 0,
+            column_index: byte_pos,
+            byte_pos,
+        };
+        LintFixChange {
+            new_text: new_text.to_string(),
+            range: Range {
+                start: position(start),
+                end: position(end),
+            },
+        }
+    }
+
+    /// 每一组修改是一个错误的修复方案
+    fn diagnostics(fixes: Vec<Vec<LintFixChange>>) -> Vec<LintDiagnostic> {
+        fixes
+            .into_iter()
+            .map(|changes| LintDiagnostic {
+                code: "test".to_string(),
+                filename: "routes.ts".to_string(),
+                hint: None,
+                message: String::new(),
+                severity: Default::default(),
+                range: changes[0].range.clone(),
+                fixes: vec![LintFix {
+                    description: String::new(),
+                    changes,
+                }],
+            })
+            .collect()
+    }
+
+    fn find_object<'view>(
+        node: ast_view::Node<'view>,
+    ) -> Option<&'view ast_view::ObjectLit<'view>> {
+        match node {
+            ast_view::Node::ObjectLit(object_lit) => Some(object_lit),
+            node => node.children().into_iter().find_map(find_object),
+        }
+    }
+
+    /// 删除源码中第一个对象上的 `keys`
+    fn remove(source: &str, keys: &[&str]) -> String {
+        let config = Config::default();
+        let parsed_source = crate::parse_program("routes.ts", source.to_string()).unwrap();
+        parsed_source.with_view(|program| {
+            let ctx = Context::new(
+                "routes.ts".to_string(),
+                parsed_source.media_type(),
+                &config,
+                parsed_source.source(),
+                program,
+            );
+            let object_lit = find_object(program.as_node()).unwrap();
+            let changes = remove_props(
+                object_lit,
+                |prop| matches!(prop_key(prop), Some((key, _)) if keys.contains(&key.as_str())),
+                &ctx,
+            );
+            apply_changes(source, &changes)
+        })
+    }
+
+    #[test]
+    fn applies_non_overlapping_fixes() {
+        let text = "abcdef";
+        let fixes = diagnostics(vec![vec![change(0, 1, "A")], vec![change(4, 6, "")]]);
+        assert_eq!(apply_fixes(text, &fixes), ("Abcd".to_string(), 2));
+    }
+
+    #[test]
+    fn applies_all_changes_of_a_fix() {
+        let text = "abcdef";
+        let fixes = diagnostics(vec![vec![change(1, 2, "B"), change(3, 3, "-")]]);
+        assert_eq!(apply_fixes(text, &fixes), ("aBc-def".to_string(), 1));
+    }
+
+    #[test]
+    fn skips_overlapping_fixes() {
+        let text = "abcdef";
+        // 第二个修复和第一个重叠，整个修复都留到下一轮，包括没有重叠的修改
+        let fixes = diagnostics(vec![
+            vec![change(1, 4, "X")],
+            vec![change(0, 1, "A"), change(3, 5, "Y")],
+            vec![change(5, 6, "F")],
+        ]);
+        assert_eq!(apply_fixes(text, &fixes), ("aXeF".to_string(), 2));
+    }
+
+    #[test]
+    fn skips_inserts_at_the_same_position() {
+        let text = "ab";
+        let fixes = diagnostics(vec![vec![change(1, 1, "1")], vec![change(1, 1, "2")]]);
+        assert_eq!(apply_fixes(text, &fixes), ("a1b".to_string(), 1));
+    }
+
+    #[test]
+    fn adjacent_changes_do_not_conflict() {
+        let text = "abcd";
+        let fixes = diagnostics(vec![vec![change(0, 2, "X")], vec![change(2, 4, "Y")]]);
+        assert_eq!(apply_fixes(text, &fixes), ("XY".to_string(), 2));
+    }
+
+    #[test]
+    fn removes_props_with_commas() {
+        let source = "export default { a: 1, b: 2, c: 3 };";
+        assert_eq!(remove(source, &["a"]), "export default { b: 2, c: 3 };");
+        assert_eq!(remove(source, &["b"]), "export default { a: 1, c: 3 };");
+        assert_eq!(remove(source, &["c"]), "export default { a: 1, b: 2 };");
+        assert_eq!(remove(source, &["a", "b"]), "export default { c: 3 };");
+        assert_eq!(remove(source, &["b", "c"]), "export default { a: 1 };");
+        assert_eq!(remove(source, &["a", "b", "c"]), "export default {  };");
+    }
+
+    #[test]
+    fn removes_last_prop_keeping_trailing_comma() {
+        let source = "export default {\n  a: 1,\n  b: 2,\n};";
+        assert_eq!(remove(source, &["b"]), "export default {\n  a: 1,\n};");
+    }
+
+    #[test]
+    fn keeps_comments_of_other_props() {
+        let source = "export default {\n  a: 1,\n  // b 的说明\n  b: 2,\n};";
+        assert_eq!(
+            remove(source, &["a"]),
+            "export default {\n  // b 的说明\n  b: 2,\n};"
+        );
+        let source = "export default {\n  a: 1, // a 的说明\n  b: 2,\n};";
+        assert_eq!(
+            remove(source, &["b"]),
+            "export default {\n  a: 1, // a 的说明\n};"
+        );
+        // 被删除的属性自己的注释一起删除
+        let source = "export default {\n  a: 1, // a 的说明\n  b: 2,\n};";
+        assert_eq!(remove(source, &["a"]), "export default {\n  b: 2,\n};");
+        let source = "export default {\n  a: 1, // a 的说明\n  // b 的说明\n  b: 2\n};";
+        assert_eq!(
+            remove(source, &["b"]),
+            "export default {\n  a: 1, // a 的说明\n};"
+        );
+    }
+
+    #[test]
+    fn generates_unified_diff() {
+        let diff = unified_diff("./config/routes.ts", "a\nb\nc\n", "a\nB\nc\n");
+        assert_eq!(
+            diff,
+            "--- a/config/routes.ts\n+++ b/config/routes.ts\n@@ -1,3 +1,3 @@\n a\n-b\n+B\n c\n"
+        );
+        assert_eq!(unified_diff("routes.ts", "a\n", "a\n"), "");
+    }
+}
@@ -0,0 +1,272 @@
+//! route-lint 的库接口，命令行、language server 和其它构建工具都通过 [`Linter`] 检查路由文件
+//!
+//! ```no_run
+//! use route_lint::{Config, Linter};
+//!
+//! let linter = Linter::new(Config::default());
+//! // 配置文件中 import 进来的路由文件也会一起检查
+//! for (path, report) in linter.lint_path("config/routes.ts".as_ref()).unwrap() {
+//!     for diagnostic in &report.diagnostics {
+//!         println!("{}: {}: {}", path.display(), diagnostic.code, diagnostic.message);
+//!     }
+//! }
+//! ```
+
+mod config;
+mod context;
+mod diagnostic;
+mod files;
+mod fix;
+mod handler;
+mod migrate;
+mod parallel;
+#[cfg(feature = "plugins")]
+mod plugin;
+mod route;
+mod route_info;
+mod rules;
+mod schema;
+mod selector;
+
+pub use config::{
+    Config, CustomRuleConfig, FieldCondition, Framework, RouteMatcher, RulesConfig, SchemaConfig,
+    UniqueNameScope, CONFIG_FILE_NAME,
+};
+pub use context::Context;
+pub use deno_ast::{MediaType, ParsedSource, SourceTextInfo};
+pub use diagnostic::{
+    display_diagnostics, LintDiagnostic, LintFix, LintFixChange, Position, Range, Severity,
+};
+pub use files::{lint_files, FileReport, FixMode, Job};
+pub use fix::unified_diff;
+pub use handler::{Control, Handler, NodeKindMask, Traverse};
+pub use migrate::children_to_routes::migrate_source as migrate_children_to_routes;
+pub use migrate::umi4::migrate_source as migrate_umi4;
+pub use migrate::{collect_source_files, MigrationResult};
+pub use parallel::default_jobs;
+pub use route_info::{route_info, FieldInfo, RouteInfo};
+pub use rules::{LintRule, ProgramRef};
+
+/// 给 benches/dispatch.rs 使用，不属于库的公开接口
+#[doc(hidden)]
+pub mod bench {
+    pub use crate::rules::{dispatch_rules, get_filtered_rules};
+}
+
+use fix::{apply_fixes, MAX_FIX_PASSES};
+use rules::{get_filtered_rules, lint_program};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/**
+ * 根据文件后缀判断文件类型，无法识别的后缀按照 TypeScript 处理
+ */
+pub fn media_type_of(file_name: &str) -> MediaType {
+    match MediaType::from(Path::new(file_name)) {
+        MediaType::Unknown => MediaType::TypeScript,
+        media_type => media_type,
+    }
+}
+
+/**
+ * 把文件内容转化为语法树，语法由文件后缀决定
+ */
+pub fn parse_program(
+    file_name: &str,
+    source_code: String,
+) -> Result<ParsedSource, deno_ast::Diagnostic> {
+    parse_program_with_media_type(file_name, source_code, media_type_of(file_name))
+}
+
+/**
+ * 按照指定的文件类型把文件内容转化为语法树
+ */
+pub fn parse_program_with_media_type(
+    file_name: &str,
+    source_code: String,
+    media_type: MediaType,
+) -> Result<ParsedSource, deno_ast::Diagnostic> {
+    let media_type = match media_type {
+        MediaType::Unknown => MediaType::TypeScript,
+        media_type => media_type,
+    };
+    let syntax = match media_type {
+        // 很多项目在 .js 的路由文件中也会写 `element: <Home />`
+        MediaType::JavaScript | MediaType::Mjs | MediaType::Cjs => {
+            deno_ast::get_syntax(MediaType::Jsx)
+        }
+        // JSON 的路由数组也是一个合法的 JavaScript 表达式
+        MediaType::Json => deno_ast::get_syntax(MediaType::JavaScript),
+        media_type => deno_ast::get_syntax(media_type),
+    };
+    deno_ast::parse_program(deno_ast::ParseParams {
+        specifier: file_name.to_string(),
+        media_type,
+        source: deno_ast::SourceTextInfo::from_string(source_code),
+        capture_tokens: true,
+        maybe_syntax: Some(syntax),
+        scope_analysis: true,
+    })
+}
+
+/// 检查一个文件的结果
+pub struct LintResult {
+    /// 解析后的语法树，可以用来获取源码和展示错误
+    pub parsed_source: ParsedSource,
+    /// 按照规则顺序排列的错误
+    pub diagnostics: Vec<LintDiagnostic>,
+}
+
+/**
+ * 使用同一份配置检查多个文件，创建时根据配置选出开启的规则
+ */
+#[derive(Debug)]
+pub struct Linter {
+    config: Config,
+    rules: Vec<Arc<dyn LintRule>>,
+}
+
+impl Linter {
+    pub fn new(config: Config) -> Self {
+        let rules = get_filtered_rules(&config);
+        Linter { config, rules }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// 开启的规则，包括配置中的插件规则
+    pub fn rules(&self) -> &[Arc<dyn LintRule>] {
+        &self.rules
+    }
+
+    /**
+     * 解析源码并执行所有开启的规则，`media_type` 为 `Unknown` 时按照 TypeScript 处理
+     */
+    pub fn lint_source(
+        &self,
+        file_name: &str,
+        source_code: String,
+        media_type: MediaType,
+    ) -> Result<LintResult, String> {
+        // 转化为语法树
+        let parsed_source = parse_program_with_media_type(file_name, source_code, media_type)
+            .map_err(|err| format!("解析文件异常： `{}`: {}", file_name, err))?;
+
+        let diagnostics = parsed_source.with_view(|program| {
+            // 生成一个context，用于存储错误信息并且被各个规则消费
+            let mut context = Context::new(
+                file_name.to_string(),
+                parsed_source.media_type(),
+                &self.config,
+                parsed_source.source(),
+                program,
+            );
+
+            // 所有规则共用一次遍历
+            lint_program(&mut context, program, &self.rules);
+
+            context.diagnostics().to_vec()
+        });
+
+        Ok(LintResult {
+            parsed_source,
+            diagnostics,
+        })
+    }
+
+    /**
+     * 读取并检查文件，文件类型由后缀决定
+     * 和命令行一样会跟随 import 进来的路由文件，返回所有文件的结果，按照路径排序
+     * 在当前线程中检查，不会创建新的线程，需要并行时使用 `lint_files`
+     */
+    pub fn lint_path(&self, path: &Path) -> Result<Vec<(PathBuf, FileReport)>, String> {
+        let entry = Job {
+            path: path.to_path_buf(),
+            content: None,
+            linter: self,
+        };
+        lint_files(vec![entry], FixMode::Off, 1)
+            .into_iter()
+            .map(|(path, report)| report.map(|report| (path, report)))
+            .collect()
+    }
+
+    /**
+     * 应用自动修复并重新检查，直到没有可以应用的修复为止
+     * 返回修复后的语法树和剩下的错误
+     */
+    pub fn fix_source(
+        &self,
+        file_name: &str,
+        source_code: String,
+        media_type: MediaType,
+    ) -> Result<LintResult, String> {
+        let mut result = self.lint_source(file_name, source_code, media_type)?;
+        for _ in 0..MAX_FIX_PASSES {
+            let (fixed, applied) = apply_fixes(
+                result.parsed_source.source().text_str(),
+                &result.diagnostics,
+            );
+            if applied == 0 {
+                break;
+            }
+            result = self.lint_source(file_name, fixed, media_type)?;
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint(file_name: &str, source: &str) -> Result<Vec<String>, String> {
+        let result = Linter::new(Config::default()).lint_source(
+            file_name,
+            source.to_string(),
+            media_type_of(file_name),
+        )?;
+        Ok(result
+            .diagnostics
+            .into_iter()
+            .map(|diagnostic| diagnostic.code)
+            .collect())
+    }
+
+    #[test]
+    fn parses_jsx_in_js_files() {
+        let source = "export default [{ path: '/a', element: <A />, children: [] }];";
+        assert_eq!(media_type_of("routes.js"), MediaType::JavaScript);
+        assert!(lint("routes.js", source)
+            .unwrap()
+            .contains(&"no-use-children".to_string()));
+    }
+
+    #[test]
+    fn parses_tsx_files() {
+        let source = "const routes: Route[] = [{ path: '/a', element: <A />, children: [] }];
+export default routes as Route[];";
+        assert_eq!(media_type_of("routes.tsx"), MediaType::Tsx);
+        assert!(lint("routes.tsx", source).is_ok());
+    }
+
+    #[test]
+    fn parses_json_route_arrays() {
+        let source = r#"[{ "path": "/a", "children": [] }]"#;
+        assert_eq!(media_type_of("routes.json"), MediaType::Json);
+        assert!(lint("routes.json", source)
+            .unwrap()
+            .contains(&"no-use-children".to_string()));
+    }
+
+    #[test]
+    fn treats_unknown_extensions_as_typescript() {
+        assert_eq!(media_type_of("routes.yaml"), MediaType::TypeScript);
+        assert!(lint("routes.config", "export default [{ path: '/a' }];").is_ok());
+
+        let err = lint("routes.yaml", "- path: /a\n  component: ./A\n").unwrap_err();
+        assert!(err.starts_with("解析文件异常： `routes.yaml`"), "{}", err);
+    }
+}
@@ -0,0 +1,463 @@
+use route_lint::{
+    media_type_of, route_info, Config, LintDiagnostic, Linter, Range, RouteInfo, Severity,
+};
+
+use lsp_server::{Connection, Message, Notification, Request, RequestId, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationTrait, PublishDiagnostics,
+};
+use lsp_types::request::{CodeActionRequest, HoverRequest, Request as RequestTrait};
+use lsp_types::{
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, MarkupContent, MarkupKind, NumberOrString, Position,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    TextEdit, Url, WorkspaceEdit,
+};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/**
+ * 在标准输入输出上启动 language server，直到编辑器发送 shutdown
+ */
+pub fn run(config_path: Option<PathBuf>) -> Result<(), String> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..Default::default()
+    };
+    let capabilities = serde_json::to_value(capabilities).map_err(|err| err.to_string())?;
+    connection
+        .initialize(capabilities)
+        .map_err(|err| format!("初始化 language server 异常： {}", err))?;
+
+    let mut server = Server {
+        connection,
+        config_path,
+        documents: HashMap::new(),
+    };
+    server.main_loop()?;
+    // 需要先关闭连接，写入的线程才会退出
+    drop(server);
+
+    io_threads
+        .join()
+        .map_err(|err| format!("language server 异常退出： {}", err))
+}
+
+struct Server {
+    connection: Connection,
+    /// 命令行中指定的配置文件，没有指定时从文档所在目录向上查找
+    config_path: Option<PathBuf>,
+    /// 编辑器中打开的文档，内容以编辑器为准，可能还没有保存
+    documents: HashMap<Url, Document>,
+}
+
+struct Document {
+    source: String,
+    /// 最近一次检查的结果，文档修改时更新，code action 直接使用
+    result: Result<(LineIndex, Vec<LintDiagnostic>), String>,
+}
+
+impl Server {
+    fn main_loop(&mut self) -> Result<(), String> {
+        let receiver = self.connection.receiver.clone();
+        for message in &receiver {
+            match message {
+                Message::Request(request) => {
+                    if self
+                        .connection
+                        .handle_shutdown(&request)
+                        .map_err(|err| err.to_string())?
+                    {
+                        return Ok(());
+                    }
+                    self.handle_request(request)?;
+                }
+                Message::Notification(notification) => self.handle_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn handle_request(&mut self, request: Request) -> Result<(), String> {
+        let response = match request.method.as_str() {
+            HoverRequest::METHOD => {
+                let (id, params) = extract_request::<HoverRequest>(request)?;
+                Response::new_ok(id, self.hover(params))
+            }
+            CodeActionRequest::METHOD => {
+                let (id, params) = extract_request::<CodeActionRequest>(request)?;
+                Response::new_ok(id, self.code_actions(params))
+            }
+            _ => Response::new_err(
+                request.id,
+                lsp_server::ErrorCode::MethodNotFound as i32,
+                format!("不支持的请求 `{}`", request.method),
+            ),
+        };
+        self.send(Message::Response(response))
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> Result<(), String> {
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams = extract_notification(notification)?;
+                self.update(params.text_document.uri, params.text_document.text)
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams = extract_notification(notification)?;
+                let uri = params.text_document.uri;
+                // 使用的是全量同步，最后一次修改就是完整的内容
+                match params.content_changes.into_iter().last() {
+                    Some(change) => self.update(uri, change.text),
+                    None => Ok(()),
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams = extract_notification(notification)?;
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+                // 关闭文件之后清空错误
+                self.notify::<PublishDiagnostics>(PublishDiagnosticsParams {
+                    uri,
+                    diagnostics: vec![],
+                    version: None,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /**
+     * 保存文档的最新内容并重新检查
+     */
+    fn update(&mut self, uri: Url, source: String) -> Result<(), String> {
+        let result = self.lint(&uri, &source);
+        let document = Document { source, result };
+        self.publish_diagnostics(uri.clone(), &document)?;
+        self.documents.insert(uri, document);
+        Ok(())
+    }
+
+    fn publish_diagnostics(&self, uri: Url, document: &Document) -> Result<(), String> {
+        let diagnostics = match &document.result {
+            Ok((text, diagnostics)) => diagnostics
+                .iter()
+                .map(|diagnostic| to_lsp_diagnostic(text, diagnostic))
+                .collect(),
+            // 输入过程中经常会出现语法错误，直接把解析错误展示在第一行
+            Err(message) => vec![Diagnostic {
+                range: lsp_types::Range::default(),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("route-lint".to_string()),
+                message: message.clone(),
+                ..Default::default()
+            }],
+        };
+        self.notify::<PublishDiagnostics>(PublishDiagnosticsParams {
+            uri,
+            diagnostics,
+            version: None,
+        })
+    }
+
+    /**
+     * 把修复方案转换为 quickfix，只返回和选中范围有交集的错误
+     */
+    fn code_actions(&self, params: CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = params.text_document.uri;
+        let (text, diagnostics) = match self.documents.get(&uri).map(|document| &document.result) {
+            Some(Ok(result)) => result,
+            _ => return vec![],
+        };
+        let start = text.offset(params.range.start);
+        let end = text.offset(params.range.end);
+
+        let mut actions = vec![];
+        for diagnostic in diagnostics {
+            if diagnostic.range.start.byte_pos > end || diagnostic.range.end.byte_pos < start {
+                continue;
+            }
+            for fix in &diagnostic.fixes {
+                let edits = fix
+                    .changes
+                    .iter()
+                    .map(|change| TextEdit {
+                        range: text.range(&change.range),
+                        new_text: change.new_text.clone(),
+                    })
+                    .collect();
+                actions.push(CodeActionOrCommand::CodeAction(CodeAction {
+                    title: fix.description.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    diagnostics: Some(vec![to_lsp_diagnostic(text, diagnostic)]),
+                    edit: Some(WorkspaceEdit {
+                        changes: Some(HashMap::from([(uri.clone(), edits)])),
+                        ..Default::default()
+                    }),
+                    is_preferred: Some(true),
+                    ..Default::default()
+                }));
+            }
+        }
+        actions
+    }
+
+    /**
+     * 展示光标所在路由的完整路径，以及从父路由继承的 access 和 layout
+     */
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let source = &self.documents.get(&uri)?.source;
+        let config = self.config(&uri);
+        let file_name = file_name(&uri);
+        let ast = route_lint::parse_program(&file_name, source.clone()).ok()?;
+        let text = LineIndex::new(source, ast.source().text_str());
+        let offset = text.offset(params.text_document_position_params.position);
+
+        let routes = route_info(&ast, config.framework);
+        let chain = route_chain(&routes, offset)?;
+        let route = chain.last()?;
+
+        let mut lines = vec![format!("**路由** `{}`", route.full_path)];
+        if let Some(line) = inherited_field(&chain, "access") {
+            lines.push(line);
+        }
+        if let Some(line) = inherited_field(&chain, "layout") {
+            lines.push(line);
+        }
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: lines.join("\n\n"),
+            }),
+            range: Some(text.range(&route.range)),
+        })
+    }
+
+    /**
+     * 检查编辑器中的文档，返回文档的行信息和错误，解析失败时返回错误信息
+     */
+    fn lint(&self, uri: &Url, source: &str) -> Result<(LineIndex, Vec<LintDiagnostic>), String> {
+        let config = self.config(uri);
+        let file_name = file_name(uri);
+        Linter::new(config)
+            .lint_source(&file_name, source.to_string(), media_type_of(&file_name))
+            .map(|result| {
+                let text = result.parsed_source.source().text_str();
+                (LineIndex::new(source, text), result.diagnostics)
+            })
+    }
+
+    fn config(&self, uri: &Url) -> Config {
+        let path = uri.to_file_path().unwrap_or_default();
+        match self.config_path.clone().or_else(|| Config::discover(&path)) {
+            Some(config_path) => Config::load(&config_path).unwrap_or_else(|err| {
+                eprintln!("{}", err);
+                Config::default()
+            }),
+            None => Config::default(),
+        }
+    }
+
+    fn notify<N: NotificationTrait>(&self, params: N::Params) -> Result<(), String> {
+        self.send(Message::Notification(Notification::new(
+            N::METHOD.to_string(),
+            params,
+        )))
+    }
+
+    fn send(&self, message: Message) -> Result<(), String> {
+        self.connection
+            .sender
+            .send(message)
+            .map_err(|err| err.to_string())
+    }
+}
+
+fn extract_request<R: RequestTrait>(request: Request) -> Result<(RequestId, R::Params), String> {
+    request
+        .extract(R::METHOD)
+        .map_err(|err| format!("解析请求异常： {:?}", err))
+}
+
+fn extract_notification<P: serde::de::DeserializeOwned>(
+    notification: Notification,
+) -> Result<P, String> {
+    serde_json::from_value(notification.params).map_err(|err| format!("解析通知异常： {}", err))
+}
+
+/**
+ * 文档对应的文件名，用于判断文件类型和展示错误
+ */
+fn file_name(uri: &Url) -> String {
+    match uri.to_file_path() {
+        Ok(path) => path.display().to_string(),
+        Err(_) => uri.path().to_string(),
+    }
+}
+
+fn to_lsp_diagnostic(text: &LineIndex, diagnostic: &LintDiagnostic) -> Diagnostic {
+    let message = match &diagnostic.hint {
+        Some(hint) => format!("{}\n{}", diagnostic.message, hint),
+        None => diagnostic.message.clone(),
+    };
+    Diagnostic {
+        range: text.range(&diagnostic.range),
+        severity: Some(match diagnostic.severity {
+            Severity::Error => DiagnosticSeverity::ERROR,
+            Severity::Warning => DiagnosticSeverity::WARNING,
+        }),
+        code: Some(NumberOrString::String(diagnostic.code.clone())),
+        source: Some("route-lint".to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/**
+ * 找到包含 `offset` 的最里层路由，返回从顶层到它的所有路由
+ */
+fn route_chain(routes: &[RouteInfo], offset: usize) -> Option<Vec<&RouteInfo>> {
+    routes.iter().find_map(|route| {
+        if offset < route.range.start.byte_pos || offset > route.range.end.byte_pos {
+            return None;
+        }
+        let mut chain = vec![route];
+        if let Some(children) = route_chain(&route.routes, offset) {
+            chain.extend(children);
+        }
+        Some(chain)
+    })
+}
+
+/**
+ * 从当前路由开始向上查找属性，父路由上的属性会注明继承自哪个路由
+ */
+fn inherited_field(chain: &[&RouteInfo], key: &str) -> Option<String> {
+    let (index, value) = chain
+        .iter()
+        .enumerate()
+        .rev()
+        .find_map(|(index, route)| route.field(key).map(|value| (index, value)))?;
+    let value = &value.text;
+    if index + 1 == chain.len() {
+        Some(format!("{}: `{}`", key, value))
+    } else {
+        Some(format!(
+            "{}: `{}`（继承自 `{}`）",
+            key, value, chain[index].full_path
+        ))
+    }
+}
+
+/**
+ * 把字节位置转换为 LSP 使用的 UTF-16 位置，每一行的开始位置只在创建时计算一次
+ */
+struct LineIndex {
+    /// 解析后的源码，BOM 已经去掉，和诊断中的 byte_pos 对应
+    text: String,
+    /// 每一行开始的字节位置
+    line_starts: Vec<usize>,
+    /// 编辑器中的文档以 BOM 开头时，第一行多出一个字符
+    bom: bool,
+}
+
+impl LineIndex {
+    fn new(source: &str, text: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(text.match_indices('\n').map(|(index, _)| index + 1))
+            .collect();
+        LineIndex {
+            text: text.to_string(),
+            line_starts,
+            bom: source.starts_with('\u{feff}'),
+        }
+    }
+
+    fn position(&self, byte_pos: usize) -> Position {
+        let byte_pos = byte_pos.min(self.text.len());
+        let line = self.line_starts.partition_point(|start| *start <= byte_pos) - 1;
+        let line_start = self.line_starts[line];
+        let mut character = self.text[line_start..byte_pos].encode_utf16().count();
+        if self.bom && line == 0 {
+            character += 1;
+        }
+        Position::new(line as u32, character as u32)
+    }
+
+    fn range(&self, range: &Range) -> lsp_types::Range {
+        lsp_types::Range {
+            start: self.position(range.start.byte_pos),
+            end: self.position(range.end.byte_pos),
+        }
+    }
+
+    fn offset(&self, position: Position) -> usize {
+        let line_start = match self.line_starts.get(position.line as usize) {
+            Some(line_start) => *line_start,
+            None => return self.text.len(),
+        };
+        let mut character = position.character as usize;
+        if self.bom && position.line == 0 {
+            character = character.saturating_sub(1);
+        }
+        let mut utf16 = 0;
+        for (index, char) in self.text[line_start..].char_indices() {
+            if utf16 >= character || char == '\n' {
+                return line_start + index;
+            }
+            utf16 += char.len_utf16();
+        }
+        self.text.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_cjk_and_emoji_to_utf16() {
+        let text = "名称\n  a😀b\n";
+        let index = LineIndex::new(text, text);
+        // 中文在 UTF-8 中占 3 个字节，在 UTF-16 中占 1 个单位
+        assert_eq!(index.position(3), Position::new(0, 1));
+        assert_eq!(index.position(6), Position::new(0, 2));
+        // emoji 在 UTF-8 中占 4 个字节，在 UTF-16 中占 2 个单位
+        let b = text.find('b').unwrap();
+        assert_eq!(index.position(b), Position::new(1, 5));
+        assert_eq!(index.position(text.len()), Position::new(2, 0));
+
+        assert_eq!(index.offset(Position::new(0, 1)), 3);
+        assert_eq!(index.offset(Position::new(1, 5)), b);
+        assert_eq!(index.offset(Position::new(1, 3)), text.find('😀').unwrap());
+    }
+
+    #[test]
+    fn clamps_positions_out_of_range() {
+        let text = "ab\ncd";
+        let index = LineIndex::new(text, text);
+        // 超出行尾时停在换行符
+        assert_eq!(index.offset(Position::new(0, 10)), 2);
+        assert_eq!(index.offset(Position::new(5, 0)), text.len());
+        assert_eq!(index.position(100), Position::new(1, 2));
+    }
+
+    #[test]
+    fn counts_bom_on_first_line() {
+        let source = "\u{feff}名称\nab";
+        let text = source.trim_start_matches('\u{feff}');
+        let index = LineIndex::new(source, text);
+        assert_eq!(index.position(3), Position::new(0, 2));
+        assert_eq!(index.position(text.len()), Position::new(1, 2));
+        assert_eq!(index.offset(Position::new(0, 2)), 3);
+    }
+}
@@ -1,79 +1,284 @@
-pub mod context;
-pub mod diagnostic;
-pub mod handler;
-pub mod rules;
+mod lsp;
+mod watch;
 
-use context::Context;
-use diagnostic::display_diagnostics;
+use route_lint::{
+    collect_source_files, default_jobs, display_diagnostics, lint_files,
+    migrate_children_to_routes, migrate_umi4, unified_diff, Config, FixMode, Job, Linter,
+    MigrationResult, Severity,
+};
 use std::string::String;
 use structopt::StructOpt;
 
-use crate::rules::get_all_rules_raw;
 /// Search for a pattern in a file and display the lines that contain it.
 #[derive(StructOpt)]
 struct Cli {
-    /// The path to the file to read
+    #[structopt(subcommand)]
+    command: Option<Command>,
+
+    /// 需要检查的路由文件，可以同时检查多个
     #[structopt(parse(from_os_str))]
-    path: std::path::PathBuf,
+    paths: Vec<std::path::PathBuf>,
+
+    /// 同时检查的文件数量，默认和 CPU 的数量一致
+    #[structopt(long, short = "j")]
+    jobs: Option<usize>,
+
+    /// 配置文件路径，默认从文件所在目录向上查找 .routelintrc.json
+    #[structopt(long, parse(from_os_str))]
+    config: Option<std::path::PathBuf>,
+
+    /// 自动修复可以修复的问题，并写回文件
+    #[structopt(long)]
+    fix: bool,
+
+    /// 只打印自动修复会产生的 diff，不修改文件
+    #[structopt(long, conflicts_with = "fix")]
+    fix_dry_run: bool,
+
+    /// 从标准输入读取需要检查的源码
+    #[structopt(long, conflicts_with_all = &["paths", "fix"])]
+    stdin: bool,
+
+    /// 标准输入对应的文件名，用于展示错误、查找配置文件和判断文件类型
+    #[structopt(long, parse(from_os_str), requires = "stdin")]
+    stdin_filename: Option<std::path::PathBuf>,
+
+    /// 持续运行，路由文件以及引用的 access、locales、pages 变化时重新检查
+    #[structopt(long, conflicts_with_all = &["stdin", "fix-dry-run"])]
+    watch: bool,
+}
+
+#[derive(StructOpt)]
+enum Command {
+    /// 批量迁移项目中的路由配置
+    Migrate(Migration),
+    /// 通过标准输入输出启动 language server，给编辑器提供实时检查
+    Lsp,
+}
+
+#[derive(StructOpt)]
+enum Migration {
+    /// 把所有层级路由中的 children 改为 routes，注释和格式保持不变
+    ChildrenToRoutes {
+        /// 项目目录或者单个文件
+        #[structopt(parse(from_os_str), default_value = ".")]
+        path: std::path::PathBuf,
+
+        /// 只打印迁移会产生的 diff，不修改文件
+        #[structopt(long)]
+        dry_run: bool,
+    },
+    /// 按照 umi 4 的路由语义检查并迁移，无法自动处理的地方会给出提示
+    Umi4 {
+        /// 项目目录或者单个文件
+        #[structopt(parse(from_os_str), default_value = ".")]
+        path: std::path::PathBuf,
+
+        /// 只打印迁移会产生的 diff，不修改文件
+        #[structopt(long)]
+        dry_run: bool,
+    },
 }
 
 #[derive(Debug)]
 struct ReadFileError(String);
 
 /**
- * 把文件内容转化为语法树
+ * 读取配置文件，没有找到就使用默认配置
  */
-fn parse_program(
-    file_name: &str,
-    syntax: deno_ast::swc::parser::Syntax,
-    source_code: String,
-) -> Result<deno_ast::ParsedSource, deno_ast::Diagnostic> {
-    deno_ast::parse_program(deno_ast::ParseParams {
-        specifier: file_name.to_string(),
-        media_type: deno_ast::MediaType::Unknown,
-        source: deno_ast::SourceTextInfo::from_string(source_code),
-        capture_tokens: true,
-        maybe_syntax: Some(syntax),
-        scope_analysis: true,
-    })
+fn load_config(
+    config_path: Option<std::path::PathBuf>,
+    path: &std::path::Path,
+) -> Result<Config, ReadFileError> {
+    match config_path.or_else(|| Config::discover(path)) {
+        Some(config_path) => Config::load(&config_path).map_err(ReadFileError),
+        None => Ok(Config::default()),
+    }
+}
+
+/**
+ * 对项目中的每个文件执行迁移，`migrate_source` 返回迁移后的源码和修改的数量
+ */
+fn run_migration(
+    root: &std::path::Path,
+    config_path: Option<std::path::PathBuf>,
+    dry_run: bool,
+    migrate_source: impl Fn(&str, String, &Config) -> Result<MigrationResult, String>,
+) -> Result<(), ReadFileError> {
+    let files = collect_source_files(root)
+        .map_err(|err| ReadFileError(format!("读取目录异常： `{}`: {}", root.display(), err)))?;
+
+    let mut total = 0;
+    for file in files {
+        let file_str = file.display().to_string();
+        let content = std::fs::read_to_string(&file)
+            .map_err(|err| ReadFileError(format!("读取文件异常： `{}`: {}", file_str, err)))?;
+        let config = load_config(config_path.clone(), &file)?;
+
+        let result = match migrate_source(&file_str, content.clone(), &config) {
+            Ok(result) => result,
+            Err(err) => {
+                eprintln!("⚠️ 跳过 {}", err);
+                continue;
+            }
+        };
+        total += result.count + result.diagnostics.len();
+
+        if result.count > 0 {
+            // 解析时会去掉 BOM，写回文件时需要保留
+            let bom = if content.starts_with('\u{feff}') {
+                "\u{feff}"
+            } else {
+                ""
+            };
+            let migrated = format!("{}{}", bom, result.source);
+            if dry_run {
+                print!("{}", unified_diff(&file_str, &content, &migrated));
+            } else {
+                std::fs::write(&file, migrated).map_err(|err| {
+                    ReadFileError(format!("写入文件异常： `{}`: {}", file_str, err))
+                })?;
+                println!("🔧 {}: 迁移了 {} 处", file_str, result.count);
+            }
+        }
+
+        // 需要人工确认的问题，位置对应迁移前的源码
+        if !result.diagnostics.is_empty() {
+            let source = deno_ast::SourceTextInfo::from_string(content);
+            display_diagnostics(&result.diagnostics, &source);
+        }
+    }
+
+    if total == 0 {
+        println!("👍 没有需要迁移的路由配置");
+    }
+    Ok(())
+}
+
+/// 所有文件的检查结果
+struct Summary {
+    /// 检查过的文件，包括 import 进来的路由文件，监听模式会监听这些文件的变化
+    files: Vec<std::path::PathBuf>,
+    /// 第一个读取或者解析失败的文件的错误，其它文件仍然会正常检查
+    failure: Option<ReadFileError>,
+    /// 问题的数量
+    total: usize,
+    /// error 级别的问题数量，不为 0 时命令行的退出码为 1
+    errors: usize,
+}
+
+/**
+ * 检查入口文件以及 import 进来的路由文件，按照文件路径输出检查结果
+ */
+fn lint_entries(entries: Vec<Job>, fix: FixMode, jobs: usize) -> Summary {
+    let reports = lint_files(entries, fix, jobs);
+    let files = reports.iter().map(|(path, _)| path.clone()).collect();
+    let mut failure = None;
+    let mut total = 0;
+    let mut errors = 0;
+    for (_, report) in reports {
+        let report = match report {
+            Ok(report) => report,
+            Err(err) => {
+                failure.get_or_insert(ReadFileError(err));
+                continue;
+            }
+        };
+        print!("{}", report.output);
+        total += report.diagnostics.len();
+        errors += report
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .count();
+        display_diagnostics(&report.diagnostics, &report.source);
+        for warning in report.warnings {
+            eprintln!("{}", warning);
+        }
+    }
+    Summary {
+        files,
+        failure,
+        total,
+        errors,
+    }
 }
 
 fn main() -> Result<(), ReadFileError> {
     let args = Cli::from_args();
-    let path = &args.path;
-
-    // display 可以转化成需要显示的文案
-    let path_str: String = path.as_path().display().to_string();
-
-    // 读取文件内容
-    let content = std::fs::read_to_string(&args.path)
-        .map_err(|err| ReadFileError(format!("读取文件异常： `{}`: {}", path_str, err)))?;
-
-    // 定义一下是一个 ts ast 的格式
-    let syntax = deno_ast::get_syntax(deno_ast::MediaType::TypeScript);
-    // 转化为语法树
-    let ast = parse_program(&path_str, syntax, content).unwrap();
-
-    ast.with_view(|program| {
-        // 生成一个context，用于存储错误信息并且被各个规则消费
-        let mut context = Context::new(
-            path_str.clone(),
-            deno_ast::MediaType::TypeScript,
-            ast.source(),
-            program,
-        );
-
-        let rules = get_all_rules_raw();
-        for rule in rules {
-            rule.lint_program_with_ast_view(&mut context, program);
-        }
 
-        if context.diagnostics().is_empty() {
-            println!("👍 没有发现任何问题，非常好!");
+    if let Some(Command::Lsp) = args.command {
+        return lsp::run(args.config).map_err(ReadFileError);
+    }
+    if let Some(Command::Migrate(migration)) = args.command {
+        return match migration {
+            Migration::ChildrenToRoutes { path, dry_run } => {
+                run_migration(&path, args.config, dry_run, migrate_children_to_routes)
+            }
+            Migration::Umi4 { path, dry_run } => {
+                run_migration(&path, args.config, dry_run, migrate_umi4)
+            }
+        };
+    }
+
+    let jobs = args.jobs.unwrap_or_else(default_jobs).max(1);
+
+    // 标准输入的内容当作 `--stdin-filename` 这个文件来检查
+    let mut entries = if args.stdin {
+        let mut content = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut content)
+            .map_err(|err| ReadFileError(format!("读取标准输入异常： {}", err)))?;
+        let path = args
+            .stdin_filename
+            .unwrap_or_else(|| std::path::PathBuf::from("<stdin>"));
+        vec![(path, Some(content))]
+    } else if args.paths.is_empty() {
+        return Err(ReadFileError("请指定需要检查的路由文件".to_string()));
+    } else {
+        args.paths.into_iter().map(|path| (path, None)).collect()
+    };
+    // 监听模式下每次重新检查时都会重新读取配置
+    if args.watch {
+        if entries.len() > 1 {
+            return Err(ReadFileError("监听模式只支持一个路由文件".to_string()));
         }
+        let (path, _) = entries.remove(0);
+        return watch::run(path, args.config, args.fix, jobs).map_err(ReadFileError);
+    }
+
+    // 每个入口文件使用各自目录中的配置
+    let linters = entries
+        .iter()
+        .map(|(path, _)| load_config(args.config.clone(), path).map(Linter::new))
+        .collect::<Result<Vec<_>, _>>()?;
+    let entries = entries
+        .into_iter()
+        .zip(&linters)
+        .map(|((path, content), linter)| Job {
+            path,
+            content,
+            linter,
+        })
+        .collect();
 
-        display_diagnostics(&context.diagnostics(), ast.source());
-    });
+    let fix = if args.fix {
+        FixMode::Write
+    } else if args.fix_dry_run {
+        FixMode::DryRun
+    } else {
+        FixMode::Off
+    };
+    let summary = lint_entries(entries, fix, jobs);
+    if let Some(err) = summary.failure {
+        return Err(err);
+    }
+    if summary.total == 0 && !args.fix_dry_run {
+        println!("👍 没有发现任何问题，非常好!");
+    }
+    // 只有 warning 的时候不影响 CI
+    if summary.errors > 0 {
+        std::process::exit(1);
+    }
 
     Ok(())
 }
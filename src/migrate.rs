@@ -0,0 +1,63 @@
+use crate::diagnostic::LintDiagnostic;
+use std::path::{Path, PathBuf};
+
+pub mod children_to_routes;
+pub mod umi4;
+
+/// 一个文件的迁移结果
+pub struct MigrationResult {
+    /// 迁移后的源码
+    pub source: String,
+    /// 自动修改的数量
+    pub count: usize,
+    /// 需要人工确认的问题，位置对应迁移前的源码
+    pub diagnostics: Vec<LintDiagnostic>,
+}
+
+/// 扫描项目时跳过的目录
+const IGNORED_DIRS: &[&str] = &[
+    "node_modules",
+    ".git",
+    ".umi",
+    ".umi-production",
+    "dist",
+    "build",
+];
+
+/// 可能包含路由配置的文件后缀
+pub const SOURCE_EXTENSIONS: &[&str] = &["ts", "tsx", "js", "jsx"];
+
+/**
+ * 收集项目中所有可能包含路由配置的源码文件，结果按路径排序
+ */
+pub fn collect_source_files(root: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    if root.is_file() {
+        files.push(root.to_path_buf());
+        return Ok(files);
+    }
+    visit_dir(root, &mut files)?;
+    files.sort();
+    Ok(files)
+}
+
+fn visit_dir(dir: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("");
+        if path.is_dir() {
+            if !IGNORED_DIRS.contains(&name) {
+                visit_dir(&path, files)?;
+            }
+        } else if matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some(ext) if SOURCE_EXTENSIONS.contains(&ext)
+        ) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use crate::config::{Config, Framework};
+use crate::context::Context;
+use crate::diagnostic::LintFixChange;
+use crate::fix::apply_changes;
+use crate::migrate::MigrationResult;
+use crate::route::{has_prop, prop_key, prop_value, RouteTree};
+
+use deno_ast::view as ast_view;
+
+/**
+ * 把所有层级路由上的 children 改为 routes，返回迁移后的源码和修改的数量
+ * 只替换 key 本身，注释和格式都会原样保留
+ */
+pub fn migrate_source(
+    file_name: &str,
+    source_code: String,
+    config: &Config,
+) -> Result<MigrationResult, String> {
+    let ast = crate::parse_program(file_name, source_code)
+        .map_err(|err| format!("解析文件异常： `{}`: {}", file_name, err))?;
+
+    let changes = ast.with_view(|program| {
+        let context = Context::new(
+            file_name.to_string(),
+            ast.media_type(),
+            config,
+            ast.source(),
+            program,
+        );
+        let mut changes = vec![];
+        for tree in RouteTree::from_program(program, Framework::Umi) {
+            tree.walk(&mut |route| {
+                if has_prop(route.object, "routes") {
+                    return;
+                }
+                if !matches!(
+                    prop_value(route.object, "children"),
+                    Some(ast_view::Expr::Array(_))
+                ) {
+                    return;
+                }
+                let key_span = route
+                    .object
+                    .props
+                    .iter()
+                    .filter_map(prop_key)
+                    .find(|(key, _)| key == "children")
+                    .map(|(_, span)| span);
+                if let Some(span) = key_span {
+                    changes.push(LintFixChange {
+                        new_text: context
+                            .file_text_substring(&span)
+                            .replace("children", "routes"),
+                        range: context.range(span),
+                    });
+                }
+            });
+        }
+        changes
+    });
+
+    Ok(MigrationResult {
+        source: apply_changes(ast.source().text_str(), &changes),
+        count: changes.len(),
+        diagnostics: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrate(source: &str) -> MigrationResult {
+        migrate_source("routes.ts", source.to_string(), &Config::default()).unwrap()
+    }
+
+    #[test]
+    fn renames_children_at_every_level() {
+        let result = migrate(
+            "export default [
+  // children 已经废弃
+  { path: '/a', children: [{ path: 'b', 'children': [{ path: 'c' }] }] },
+];",
+        );
+        assert_eq!(
+            result.source,
+            "export default [
+  // children 已经废弃
+  { path: '/a', routes: [{ path: 'b', 'routes': [{ path: 'c' }] }] },
+];"
+        );
+        assert_eq!(result.count, 2);
+        assert!(result.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn skips_routes_that_cannot_be_renamed() {
+        // 已经有 routes 时需要手动合并，children 不是数组时可能是别的含义
+        let source = "export default [
+  { path: '/a', routes: [], children: [] },
+  { path: '/b', children: childRoutes },
+  { path: '/c', children },
+];";
+        let result = migrate(source);
+        assert_eq!(result.source, source);
+        assert_eq!(result.count, 0);
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        let err = migrate_source(
+            "routes.ts",
+            "export default [".to_string(),
+            &Config::default(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.starts_with("解析文件异常"));
+    }
+}
@@ -0,0 +1,322 @@
+use crate::config::{Config, Framework};
+use crate::context::Context;
+use crate::diagnostic::LintFixChange;
+use crate::fix::{apply_changes, remove_props};
+use crate::migrate::MigrationResult;
+use crate::route::{has_prop, prop_key, prop_value, Route, RouteTree};
+
+use deno_ast::swc::common::{Span, Spanned};
+use deno_ast::view as ast_view;
+use deno_ast::MediaType;
+
+const WRAPPERS_MESSAGE: &str =
+    "🚨 umi 4 中 wrappers 需要通过 <Outlet /> 渲染子路由，请确认 wrappers 中的组件已经改写！";
+const OUTLET_MESSAGE: &str =
+    "🚨 umi 4 中布局组件需要使用 <Outlet /> 渲染子路由，不再通过 props.children 传入！";
+const LAYOUT_MESSAGE: &str =
+    "🚨 umi 4 中 layout: false 只在顶层路由上生效，请确认是否需要移动到顶层！";
+const PATH_PATTERN_MESSAGE: &str =
+    "🚨 react-router v6 不再支持正则路径，可选参数也需要 6.5 以上的版本，请确认这个 path！";
+
+/**
+ * 按照 umi 4 的路由语义迁移：删除 exact，去掉 `./` 相对路径前缀，
+ * 给没有 path 的 404 路由加上 `path: '*'`，其余需要人工确认的地方给出提示
+ * 只迁移 umi 项目，其它框架的配置直接返回错误
+ */
+pub fn migrate_source(
+    file_name: &str,
+    source_code: String,
+    config: &Config,
+) -> Result<MigrationResult, String> {
+    if config.framework != Framework::Umi {
+        return Err(format!(
+            "`{}`: 配置中的 framework 不是 umi，不能迁移到 umi 4",
+            file_name
+        ));
+    }
+    let ast = crate::parse_program(file_name, source_code)
+        .map_err(|err| format!("解析文件异常： `{}`: {}", file_name, err))?;
+
+    let (changes, diagnostics) = ast.with_view(|program| {
+        let mut context = Context::new(
+            file_name.to_string(),
+            ast.media_type(),
+            config,
+            ast.source(),
+            program,
+        );
+
+        let mut changes = vec![];
+        for tree in RouteTree::from_program(program, config.framework) {
+            tree.walk(&mut |route| migrate_route(route, &mut context, &mut changes));
+        }
+        (changes, context.diagnostics().to_vec())
+    });
+
+    Ok(MigrationResult {
+        source: apply_changes(ast.source().text_str(), &changes),
+        count: changes.len(),
+        diagnostics,
+    })
+}
+
+fn prop_span(object: &ast_view::ObjectLit, key: &str) -> Option<Span> {
+    object
+        .props
+        .iter()
+        .find(|prop| matches!(prop_key(prop), Some((name, _)) if name == key))
+        .map(|prop| prop.span())
+}
+
+fn migrate_route(route: &Route, ctx: &mut Context, changes: &mut Vec<LintFixChange>) {
+    let object = route.object;
+    let path = route.path.as_deref();
+
+    let has_children = has_prop(object, "routes") || has_prop(object, "children");
+    let json = ctx.media_type() == MediaType::Json;
+
+    // exact 在 umi 4 中已经没有意义，有子路由的 `./` 在 v6 中就是一个布局路由，不需要 path
+    let current_dir = matches!(path, Some("./") | Some("."));
+    changes.extend(remove_props(
+        object,
+        |prop| match prop_key(prop) {
+            Some((key, _)) => key == "exact" || (current_dir && has_children && key == "path"),
+            None => false,
+        },
+        ctx,
+    ));
+
+    // 没有子路由的 `./` 是父路由的默认页面，改为 index 路由
+    if current_dir && !has_children {
+        if let Some(span) = prop_span(object, "path") {
+            let new_text = if json {
+                "\"index\": true"
+            } else {
+                "index: true"
+            };
+            changes.push(LintFixChange {
+                new_text: new_text.to_string(),
+                range: ctx.range(span),
+            });
+        }
+    }
+
+    // `./login` 改为 `login`
+    if let Some(relative) = path.and_then(|path| path.strip_prefix("./")) {
+        if !relative.is_empty() {
+            if let Some(value) = prop_value(object, "path") {
+                let span = value.span();
+                let quote = ctx
+                    .file_text_substring(&span)
+                    .chars()
+                    .next()
+                    .unwrap_or('\'');
+                changes.push(LintFixChange {
+                    new_text: format!("{}{}{}", quote, relative, quote),
+                    range: ctx.range(span),
+                });
+            }
+        }
+    }
+
+    // v6 中没有 path 的路由不会兜底匹配，404 路由需要写成 `*`，index 路由除外
+    if path.is_none()
+        && !route.index
+        && has_prop(object, "component")
+        && !has_children
+        && !has_prop(object, "redirect")
+    {
+        if let Some(first) = object.props.first() {
+            let lo = first.span().lo;
+            let new_text = if json {
+                "\"path\": \"*\", "
+            } else {
+                "path: '*', "
+            };
+            changes.push(LintFixChange {
+                new_text: new_text.to_string(),
+                range: ctx.range(Span::new(lo, lo, Default::default())),
+            });
+        }
+    }
+
+    if let Some(span) = prop_span(object, "wrappers") {
+        ctx.add_diagnostic(span, "umi4-wrappers", WRAPPERS_MESSAGE);
+    }
+    if has_children && has_prop(object, "component") {
+        if let Some(span) = prop_span(object, "component") {
+            ctx.add_diagnostic(span, "umi4-outlet", OUTLET_MESSAGE);
+        }
+    }
+    if route.depth > 0 {
+        if let Some(ast_view::Expr::Lit(ast_view::Lit::Bool(value))) = prop_value(object, "layout")
+        {
+            if !value.value() {
+                ctx.add_diagnostic(value.span(), "umi4-layout", LAYOUT_MESSAGE);
+            }
+        }
+    }
+    if let Some(path) = path {
+        if path.contains('?') || path.contains('(') {
+            if let Some(value) = prop_value(object, "path") {
+                ctx.add_diagnostic(value.span(), "umi4-path-pattern", PATH_PATTERN_MESSAGE);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrate_file(file_name: &str, source: &str) -> MigrationResult {
+        migrate_source(file_name, source.to_string(), &Config::default()).unwrap()
+    }
+
+    fn migrate(source: &str) -> MigrationResult {
+        migrate_file("routes.ts", source)
+    }
+
+    fn codes(result: &MigrationResult) -> Vec<&str> {
+        result
+            .diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.code.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn removes_exact() {
+        let result = migrate("export default [{ path: '/a', exact: true, component: './A' }];");
+        assert_eq!(
+            result.source,
+            "export default [{ path: '/a', component: './A' }];"
+        );
+        assert_eq!(result.count, 1);
+    }
+
+    #[test]
+    fn drops_current_dir_path_of_layout_routes() {
+        let result = migrate(
+            "export default [{ path: './', component: './Layout', routes: [{ path: 'a', component: './A' }] }];",
+        );
+        assert_eq!(
+            result.source,
+            "export default [{ component: './Layout', routes: [{ path: 'a', component: './A' }] }];"
+        );
+    }
+
+    #[test]
+    fn turns_current_dir_leaf_into_index_route() {
+        let result = migrate(
+            "export default [{ path: '/a', routes: [{ path: './', component: './A' }, { path: 'b', component: './B' }] }];",
+        );
+        assert_eq!(
+            result.source,
+            "export default [{ path: '/a', routes: [{ index: true, component: './A' }, { path: 'b', component: './B' }] }];"
+        );
+
+        let result = migrate_file(
+            "routes.json",
+            r#"[{ "path": "/a", "routes": [{ "path": ".", "component": "./A" }] }]"#,
+        );
+        assert_eq!(
+            result.source,
+            r#"[{ "path": "/a", "routes": [{ "index": true, "component": "./A" }] }]"#
+        );
+    }
+
+    #[test]
+    fn strips_relative_prefix_keeping_quotes() {
+        let result = migrate(
+            "export default [{ path: '/a', routes: [{ path: './b', component: './B' }, { path: \"./c\", component: './C' }] }];",
+        );
+        assert_eq!(
+            result.source,
+            "export default [{ path: '/a', routes: [{ path: 'b', component: './B' }, { path: \"c\", component: './C' }] }];"
+        );
+        assert_eq!(result.count, 2);
+    }
+
+    #[test]
+    fn inserts_catch_all_path() {
+        let result =
+            migrate("export default [{ path: '/a', component: './A' }, { component: './404' }];");
+        assert_eq!(
+            result.source,
+            "export default [{ path: '/a', component: './A' }, { path: '*', component: './404' }];"
+        );
+
+        let result = migrate_file(
+            "routes.json",
+            r#"[{ "path": "/a", "component": "./A" }, { "component": "./404" }]"#,
+        );
+        assert_eq!(
+            result.source,
+            r#"[{ "path": "/a", "component": "./A" }, { "path": "*", "component": "./404" }]"#
+        );
+
+        // 重定向和布局路由不是 404 路由
+        let source = "export default [{ redirect: '/a', component: './A' }, { component: './Layout', routes: [] }];";
+        assert_eq!(migrate(source).source, source);
+    }
+
+    #[test]
+    fn reports_wrappers() {
+        let result = migrate(
+            "export default [{ path: '/a', wrappers: ['@/wrappers/auth'], component: './A' }];",
+        );
+        assert_eq!(codes(&result), ["umi4-wrappers"]);
+        assert_eq!(result.count, 0);
+    }
+
+    #[test]
+    fn reports_layout_components() {
+        let result = migrate(
+            "export default [{ path: '/a', component: './Layout', routes: [{ path: 'b', component: './B' }] }];",
+        );
+        assert_eq!(codes(&result), ["umi4-outlet"]);
+    }
+
+    #[test]
+    fn reports_nested_layout_false() {
+        let result = migrate(
+            "export default [{ path: '/login', layout: false, routes: [{ path: 'a', layout: false, component: './A' }] }];",
+        );
+        assert_eq!(codes(&result), ["umi4-layout"]);
+    }
+
+    #[test]
+    fn reports_path_patterns() {
+        let result = migrate(
+            "export default [{ path: '/a/:id?', component: './A' }, { path: '/b/(\\\\d+)', component: './B' }];",
+        );
+        assert_eq!(codes(&result), ["umi4-path-pattern", "umi4-path-pattern"]);
+    }
+
+    #[test]
+    fn is_idempotent() {
+        let source = "export default [
+  { path: './', component: './Layout', routes: [
+    { path: './', exact: true, component: './Home' },
+    { path: './list', component: './List' },
+    { component: './404' },
+  ] },
+];";
+        let first = migrate(source);
+        assert_ne!(first.source, source);
+        let second = migrate(&first.source);
+        assert_eq!(second.source, first.source);
+        assert_eq!(second.count, 0);
+    }
+
+    #[test]
+    fn refuses_other_frameworks() {
+        let config = Config {
+            framework: Framework::ReactRouter,
+            ..Config::default()
+        };
+        let source = "export default [{ path: '/a', exact: true, component: './A' }];";
+        assert!(migrate_source("routes.ts", source.to_string(), &config).is_err());
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// 工作线程的栈大小，和主线程保持一致，层级很深的路由配置解析时需要较大的栈
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// 默认的线程数，和 CPU 的数量一致
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism().map_or(1, |jobs| jobs.get())
+}
+
+/**
+ * 用 `jobs` 个线程处理 `items`，每个线程处理完一个再取下一个
+ * 返回的结果和 `items` 的顺序一致，和完成的顺序无关
+ */
+pub fn map<T, R>(items: &[T], jobs: usize, f: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let jobs = jobs.min(items.len());
+    if jobs <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            std::thread::Builder::new()
+                .stack_size(STACK_SIZE)
+                .spawn_scoped(scope, || loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let item = match items.get(index) {
+                        Some(item) => item,
+                        None => break,
+                    };
+                    let result = f(item);
+                    results.lock().unwrap().push((index, result));
+                })
+                .expect("创建线程失败");
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn keeps_input_order() {
+        let items: Vec<u64> = (0..32).collect();
+        // 前面的任务更慢，完成的顺序和输入的顺序相反
+        let results = map(&items, 4, |item| {
+            std::thread::sleep(Duration::from_millis(32 - item));
+            item * 2
+        });
+        assert_eq!(
+            results,
+            items.iter().map(|item| item * 2).collect::<Vec<_>>()
+        );
+    }
+}
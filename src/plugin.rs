@@ -0,0 +1,488 @@
+use crate::config::Config;
+use crate::context::Context;
+use crate::diagnostic::{LintFix, LintFixChange, Severity};
+use crate::route::{static_value, Route, RouteTree};
+use crate::rules::LintRule;
+use deno_ast::swc::common::{BytePos, Span, Spanned, DUMMY_SP};
+use deno_ast::view::{NodeTrait, Program};
+use route_lint_plugin as sdk;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+use wasmtime::{Engine, Instance, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+/// 单次检查最多执行的指令数，避免插件中的死循环卡住 route-lint
+#[cfg(not(test))]
+const FUEL: u64 = 10_000_000_000;
+/// 测试死循环的插件时不需要等那么久
+#[cfg(test)]
+const FUEL: u64 = 10_000_000;
+
+/// 插件最多使用的内存，超过之后 memory.grow 会失败
+const MEMORY_LIMIT: usize = 256 << 20;
+
+/// 插件加载失败时使用的规则名
+const PLUGIN_ERROR: &str = "plugin-error";
+
+/// 编译好的插件，按照路径和修改时间缓存，监听模式下修改插件之后会重新加载
+type Cache = HashMap<PathBuf, (Option<SystemTime>, Arc<Plugin>)>;
+
+fn engine() -> &'static Engine {
+    static ENGINE: OnceLock<Engine> = OnceLock::new();
+    ENGINE.get_or_init(|| {
+        let mut config = wasmtime::Config::new();
+        config.consume_fuel(true);
+        Engine::new(&config).expect("failed to create wasm engine")
+    })
+}
+
+/**
+ * 规则名需要是 'static 的，同一个名字只保留一份，监听模式下反复重新加载插件也不会一直占用内存
+ */
+fn intern(code: String) -> &'static str {
+    static CODES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut codes = CODES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+    match codes.get(code.as_str()) {
+        Some(code) => code,
+        None => {
+            let code: &'static str = Box::leak(code.into_boxed_str());
+            codes.insert(code);
+            code
+        }
+    }
+}
+
+/// 一个编译好的 .wasm 插件
+pub struct Plugin {
+    path: PathBuf,
+    /// 插件声明的规则名
+    code: &'static str,
+    module: Module,
+}
+
+impl std::fmt::Debug for Plugin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Plugin")
+            .field("path", &self.path)
+            .field("code", &self.code)
+            .finish()
+    }
+}
+
+impl Plugin {
+    /**
+     * 读取并编译插件，同一个文件没有修改时直接使用缓存
+     */
+    pub fn load(path: &Path) -> Result<Arc<Plugin>, String> {
+        static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+        let modified = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        let mut cache = CACHE
+            .get_or_init(Default::default)
+            .lock()
+            .map_err(|err| err.to_string())?;
+        if let Some((cached, plugin)) = cache.get(path) {
+            if *cached == modified {
+                return Ok(plugin.clone());
+            }
+        }
+
+        let error =
+            |err: wasmtime::Error| format!("加载插件异常： `{}`: {:#}", path.display(), err);
+        let module = Module::from_file(engine(), path).map_err(error)?;
+        let mut instance = PluginInstance::new(&module).map_err(error)?;
+        let version: u32 = instance.call("route_lint_abi_version", ()).map_err(error)?;
+        if version != sdk::ABI_VERSION {
+            return Err(format!(
+                "加载插件异常： `{}`: 插件的协议版本是 {}，route-lint 需要的是 {}",
+                path.display(),
+                version,
+                sdk::ABI_VERSION
+            ));
+        }
+        let metadata: sdk::Metadata = instance
+            .call_json("route_lint_metadata", None)
+            .map_err(error)?;
+
+        let plugin = Arc::new(Plugin {
+            path: path.to_path_buf(),
+            code: intern(metadata.code),
+            module,
+        });
+        cache.insert(path.to_path_buf(), (modified, plugin.clone()));
+        Ok(plugin)
+    }
+
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /**
+     * 在一个新的实例中执行插件，每个文件之间互不影响
+     */
+    pub fn check(&self, input: &sdk::LintInput) -> Result<Vec<sdk::Diagnostic>, String> {
+        let input = serde_json::to_vec(input).map_err(|err| err.to_string())?;
+        PluginInstance::new(&self.module)
+            .and_then(|mut instance| instance.call_json("route_lint_check", Some(&input)))
+            .map_err(|err| format!("执行插件异常： `{}`: {:#}", self.path.display(), err))
+    }
+}
+
+struct PluginInstance {
+    store: Store<StoreLimits>,
+    instance: Instance,
+}
+
+impl PluginInstance {
+    fn new(module: &Module) -> wasmtime::Result<PluginInstance> {
+        let limits = StoreLimitsBuilder::new().memory_size(MEMORY_LIMIT).build();
+        let mut store = Store::new(module.engine(), limits);
+        store.limiter(|limits| limits);
+        store.set_fuel(FUEL)?;
+        let instance = Instance::new(&mut store, module, &[])?;
+        Ok(PluginInstance { store, instance })
+    }
+
+    fn call<Params, Results>(&mut self, name: &str, params: Params) -> wasmtime::Result<Results>
+    where
+        Params: wasmtime::WasmParams,
+        Results: wasmtime::WasmResults,
+    {
+        self.instance
+            .get_typed_func::<Params, Results>(&mut self.store, name)?
+            .call(&mut self.store, params)
+    }
+
+    /**
+     * 把输入写入插件的内存，调用导出函数，再读出 JSON 格式的结果
+     */
+    fn call_json<T: serde::de::DeserializeOwned>(
+        &mut self,
+        name: &str,
+        input: Option<&[u8]>,
+    ) -> wasmtime::Result<T> {
+        let memory = self
+            .instance
+            .get_memory(&mut self.store, "memory")
+            .ok_or_else(|| wasmtime::Error::msg("插件没有导出 memory"))?;
+
+        let packed: u64 = match input {
+            Some(input) => {
+                let len = u32::try_from(input.len())?;
+                let ptr: u32 = self.call("route_lint_alloc", len)?;
+                memory.write(&mut self.store, ptr as usize, input)?;
+                self.call(name, (ptr, len))?
+            }
+            None => self.call(name, ())?,
+        };
+
+        let (ptr, len) = ((packed >> 32) as u32, packed as u32);
+        // 长度由插件决定，先检查范围，不能直接按照这个长度分配内存
+        let output = memory
+            .data(&self.store)
+            .get(ptr as usize..)
+            .and_then(|data| data.get(..len as usize))
+            .ok_or_else(|| wasmtime::Error::msg("插件返回的结果超出了内存范围"))?;
+        let output = serde_json::from_slice(output);
+        self.call::<(u32, u32), ()>("route_lint_dealloc", (ptr, len))?;
+        Ok(output?)
+    }
+}
+
+/**
+ * 配置中的插件对应的规则，加载失败的插件会在检查时报告错误
+ */
+pub fn plugin_rules(config: &Config) -> Vec<Arc<dyn LintRule>> {
+    config
+        .plugins
+        .iter()
+        .map(|path| -> Arc<dyn LintRule> {
+            Arc::new(PluginRule {
+                plugin: Plugin::load(path),
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct PluginRule {
+    plugin: Result<Arc<Plugin>, String>,
+}
+
+impl LintRule for PluginRule {
+    fn code(&self) -> &'static str {
+        match &self.plugin {
+            Ok(plugin) => plugin.code(),
+            Err(_) => PLUGIN_ERROR,
+        }
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
+    }
+
+    fn lint_program_with_ast_view<'view>(
+        &self,
+        context: &mut Context<'view>,
+        program: Program<'view>,
+    ) {
+        let plugin = match &self.plugin {
+            Ok(plugin) => plugin,
+            Err(err) => {
+                context.add_diagnostic(DUMMY_SP, PLUGIN_ERROR, err);
+                return;
+            }
+        };
+        let input = sdk::LintInput {
+            file_name: context.file_name().to_string(),
+            source: context.source_file().text().to_string(),
+            routes: RouteTree::from_program(program, context.config().framework)
+                .iter()
+                .flat_map(|tree| tree.routes.iter().map(to_sdk_route))
+                .collect(),
+        };
+        let diagnostics = match plugin.check(&input) {
+            Ok(diagnostics) => diagnostics,
+            Err(err) => {
+                context.add_diagnostic(DUMMY_SP, plugin.code(), err);
+                return;
+            }
+        };
+
+        for diagnostic in diagnostics {
+            if let Err(err) = report(context, plugin.code(), &input.source, diagnostic) {
+                context.add_diagnostic(DUMMY_SP, plugin.code(), err);
+            }
+        }
+    }
+}
+
+/**
+ * 把插件返回的问题转化为 route-lint 的问题，范围不合法时返回错误
+ */
+fn report(
+    context: &mut Context,
+    code: &str,
+    source: &str,
+    diagnostic: sdk::Diagnostic,
+) -> Result<(), String> {
+    let span = to_span(diagnostic.span, source)?;
+    let mut fixes = vec![];
+    for fix in diagnostic.fixes {
+        let mut changes = vec![];
+        for change in fix.changes {
+            changes.push(LintFixChange {
+                new_text: change.new_text,
+                range: context.range(to_span(change.span, source)?),
+            });
+        }
+        fixes.push(LintFix {
+            description: fix.description,
+            changes,
+        });
+    }
+    let severity = match diagnostic.severity {
+        Some(sdk::Severity::Warning) => Severity::Warning,
+        Some(sdk::Severity::Error) | None => Severity::Error,
+    };
+
+    let mut lint_diagnostic =
+        context.create_diagnostic(span, code, diagnostic.message, diagnostic.hint);
+    lint_diagnostic.severity = severity;
+    lint_diagnostic.fixes = fixes;
+    context.push_diagnostic(lint_diagnostic);
+    Ok(())
+}
+
+fn to_span(span: sdk::Span, source: &str) -> Result<Span, String> {
+    let valid = span.start <= span.end
+        && source.is_char_boundary(span.start)
+        && source.is_char_boundary(span.end);
+    if !valid {
+        return Err(format!("插件返回了无效的范围 {}..{}", span.start, span.end));
+    }
+    Ok(Span::new(
+        BytePos(span.start as u32),
+        BytePos(span.end as u32),
+        Default::default(),
+    ))
+}
+
+fn from_span(span: Span) -> sdk::Span {
+    sdk::Span {
+        start: span.lo.0 as usize,
+        end: span.hi.0 as usize,
+    }
+}
+
+fn to_sdk_route(route: &Route) -> sdk::Route {
+    let fields = route
+        .fields()
+        .into_iter()
+        .map(|(key, value)| {
+            let field = sdk::Field {
+                value: static_value(&value),
+                text: value.text().to_string(),
+                span: from_span(value.span()),
+            };
+            (key, field)
+        })
+        .collect();
+
+    sdk::Route {
+        path: route.path.clone(),
+        full_path: route.full_path.clone(),
+        depth: route.depth,
+        span: from_span(route.object.span()),
+        fields,
+        routes: route.routes.iter().map(to_sdk_route).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::LintDiagnostic;
+    use crate::{Linter, MediaType};
+    use std::io::Write;
+
+    const SOURCE: &str = "export default [{ path: '/a' }];";
+
+    /**
+     * 用 wat 生成一个插件，`check` 是 route_lint_check 的函数体，`output` 是它返回的 JSON
+     */
+    fn plugin(version: u32, check: &str, output: &str) -> tempfile::NamedTempFile {
+        let escape = |json: &str| json.replace('"', "\\\"");
+        let metadata = r#"{"code":"test-plugin"}"#;
+        let wat = format!(
+            r#"(module
+              (memory (export "memory") 1)
+              (data (i32.const 0) "{metadata}")
+              (data (i32.const 1024) "{output}")
+              (func (export "route_lint_abi_version") (result i32) i32.const {version})
+              (func (export "route_lint_metadata") (result i64) i64.const {metadata_len})
+              (func (export "route_lint_alloc") (param i32) (result i32) i32.const 4096)
+              (func (export "route_lint_dealloc") (param i32 i32))
+              (func (export "route_lint_check") (param i32 i32) (result i64) {check}))"#,
+            metadata = escape(metadata),
+            metadata_len = metadata.len(),
+            output = escape(output),
+        );
+        let mut file = tempfile::Builder::new().suffix(".wasm").tempfile().unwrap();
+        file.write_all(&wat::parse_str(wat).unwrap()).unwrap();
+        file
+    }
+
+    /// 返回 1024 开始的 `output`
+    fn returning(output: &str) -> tempfile::NamedTempFile {
+        let packed = (1024u64 << 32) | output.len() as u64;
+        plugin(sdk::ABI_VERSION, &format!("i64.const {}", packed), output)
+    }
+
+    /// 只返回插件报告的问题
+    fn lint(plugin: &tempfile::NamedTempFile) -> Vec<LintDiagnostic> {
+        let mut config = Config::default();
+        config.plugins.push(plugin.path().to_path_buf());
+        Linter::new(config)
+            .lint_source("routes.ts", SOURCE.to_string(), MediaType::TypeScript)
+            .unwrap()
+            .diagnostics
+            .into_iter()
+            .filter(|diagnostic| {
+                diagnostic.code == "test-plugin" || diagnostic.code == PLUGIN_ERROR
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reports_plugin_diagnostics() {
+        let plugin =
+            returning(r#"[{"span":{"start":18,"end":28},"message":"m","severity":"warning"}]"#);
+        let diagnostics = lint(&plugin);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "test-plugin");
+        assert_eq!(diagnostics[0].message, "m");
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(
+            &SOURCE[diagnostics[0].range.start.byte_pos..diagnostics[0].range.end.byte_pos],
+            "path: '/a'"
+        );
+    }
+
+    #[test]
+    fn rejects_abi_mismatch() {
+        let plugin = plugin(sdk::ABI_VERSION + 1, "i64.const 0", "");
+        let err = Plugin::load(plugin.path()).unwrap_err();
+        assert!(err.contains("协议版本"), "{}", err);
+
+        let diagnostics = lint(&plugin);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, PLUGIN_ERROR);
+    }
+
+    #[test]
+    fn rejects_out_of_range_spans() {
+        let outputs = [
+            r#"[{"span":{"start":0,"end":9999},"message":"m"}]"#,
+            r#"[{"span":{"start":5,"end":1},"message":"m"}]"#,
+            r#"[{"span":{"start":0,"end":1},"message":"m","fixes":[{"description":"d","changes":[{"span":{"start":0,"end":9999},"newText":""}]}]}]"#,
+        ];
+        for output in outputs {
+            let diagnostics = lint(&returning(output));
+            assert_eq!(diagnostics.len(), 1, "{}", output);
+            assert_eq!(diagnostics[0].code, "test-plugin");
+            assert!(diagnostics[0].message.contains("无效的范围"), "{}", output);
+        }
+    }
+
+    #[test]
+    fn stops_when_fuel_runs_out() {
+        let plugin = plugin(sdk::ABI_VERSION, "(loop (br 0)) i64.const 0", "");
+        let diagnostics = lint(&plugin);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, "test-plugin");
+        assert!(diagnostics[0].message.contains("执行插件异常"));
+    }
+
+    #[test]
+    fn rejects_output_outside_memory() {
+        let packed = (1024u64 << 32) | u32::MAX as u64;
+        let plugin = plugin(sdk::ABI_VERSION, &format!("i64.const {}", packed), "[]");
+        let diagnostics = lint(&plugin);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(
+            diagnostics[0].message.contains("超出了内存范围"),
+            "{}",
+            diagnostics[0].message
+        );
+    }
+
+    #[test]
+    fn limits_memory() {
+        // 超过 MEMORY_LIMIT 时 memory.grow 返回 -1，这时插件执行 unreachable，否则返回 `[]`
+        let grow = |pages: usize| {
+            let check = format!(
+                "(if (i32.eq (memory.grow (i32.const {})) (i32.const -1)) (then unreachable)) i64.const {}",
+                pages,
+                (1024u64 << 32) | 2
+            );
+            lint(&plugin(sdk::ABI_VERSION, &check, "[]"))
+        };
+        assert!(grow(1).is_empty());
+
+        let diagnostics = grow(MEMORY_LIMIT / 65536);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].message.contains("执行插件异常"));
+    }
+
+    #[test]
+    fn interns_codes() {
+        let code = intern("test-intern".to_string());
+        assert!(std::ptr::eq(code, intern("test-intern".to_string())));
+    }
+}
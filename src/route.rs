@@ -0,0 +1,610 @@
+use deno_ast::swc::common::{Span, Spanned};
+use deno_ast::view::{self as ast_view, Node, NodeKind, NodeTrait};
+
+use crate::config::Framework;
+use crate::handler::{Control, NodeKindMask};
+use crate::schema::ValueKind;
+use std::collections::BTreeMap;
+
+/// 子路由可能使用的 key
+pub const ROUTES_KEYS: &[&str] = &["routes", "children"];
+
+/// react-router 中接收路由数组的函数
+pub const ROUTER_FACTORIES: &[&str] = &[
+    "createBrowserRouter",
+    "createHashRouter",
+    "createMemoryRouter",
+    "createStaticRouter",
+    "useRoutes",
+];
+
+/**
+ * 获取对象属性的 key，只处理 `key: value` 和简写属性，计算属性返回 None
+ */
+pub fn prop_key(prop: &ast_view::PropOrSpread) -> Option<(String, Span)> {
+    match prop {
+        ast_view::PropOrSpread::Prop(ast_view::Prop::KeyValue(kv)) => prop_name(&kv.key),
+        ast_view::PropOrSpread::Prop(ast_view::Prop::Shorthand(ident)) => {
+            Some((ident.sym().to_string(), ident.span()))
+        }
+        _ => None,
+    }
+}
+
+pub fn prop_name(name: &ast_view::PropName) -> Option<(String, Span)> {
+    match name {
+        ast_view::PropName::Ident(ident) => Some((ident.sym().to_string(), ident.span())),
+        ast_view::PropName::Str(str) => Some((str.value().to_string(), str.span())),
+        _ => None,
+    }
+}
+
+/// 不会包含路由配置的节点：JSX、类型声明，以及可能是路由对象上 loader、beforeEnter 之类的函数
+pub const NON_ROUTE_KINDS: NodeKindMask = NodeKindMask::of(&[
+    NodeKind::JSXElement,
+    NodeKind::JSXFragment,
+    NodeKind::TsTypeAnn,
+    NodeKind::TsTypeAliasDecl,
+    NodeKind::TsInterfaceDecl,
+    NodeKind::ArrowExpr,
+    NodeKind::FnExpr,
+    NodeKind::MethodProp,
+]);
+
+/**
+ * 只检查路由的规则可以在 on_enter_node 中调用，跳过不会包含路由配置的子树
+ * 函数只有作为路由对象的属性时才跳过，函数中返回的路由数组仍然需要检查
+ */
+pub fn skip_non_route(node: Node) -> Control {
+    let skip = match node {
+        Node::ArrowExpr(_) | Node::FnExpr(_) => matches!(
+            unwrap_parent(node),
+            Some(Node::KeyValueProp(kv)) if is_route_object(kv.parent())
+        ),
+        Node::MethodProp(method) => is_route_object(method.parent()),
+        node => NON_ROUTE_KINDS.contains(node.kind()),
+    };
+    if skip {
+        Control::SkipChildren
+    } else {
+        Control::Continue
+    }
+}
+
+/**
+ * 跳过 `as`、括号之类不影响取值的包裹节点，返回真正的父节点
+ */
+fn unwrap_parent(node: Node) -> Option<Node> {
+    let mut parent = node.parent()?;
+    while matches!(
+        parent,
+        Node::ParenExpr(_)
+            | Node::TsAsExpr(_)
+            | Node::TsConstAssertion(_)
+            | Node::TsTypeAssertion(_)
+    ) {
+        parent = parent.parent()?;
+    }
+    Some(parent)
+}
+
+/**
+ * 判断是不是 `createBrowserRouter([...])` 这种调用的参数
+ */
+fn is_router_factory_arg(arg: &ast_view::ExprOrSpread) -> bool {
+    match unwrap_parent(arg.into()) {
+        Some(Node::CallExpr(call)) => match &call.callee {
+            ast_view::ExprOrSuper::Expr(ast_view::Expr::Ident(ident)) => {
+                ROUTER_FACTORIES.contains(&ident.sym().as_ref())
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/**
+ * 判断一个数组是不是路由数组：默认导出或者 .json 文件顶层的数组、名字里带 routes 的变量、
+ * 传给 createBrowserRouter 之类函数的数组，或者是路由对象上 routes / children 的值
+ */
+pub fn is_routes_array(array_lit: &ast_view::ArrayLit) -> bool {
+    match unwrap_parent(array_lit.into()) {
+        Some(Node::ExprOrSpread(arg)) => is_router_factory_arg(arg),
+        Some(Node::ExportDefaultExpr(_)) => true,
+        // .json 路由文件中顶层的数组
+        Some(Node::ExprStmt(stmt)) => matches!(stmt.parent(), Node::Module(_) | Node::Script(_)),
+        Some(Node::VarDeclarator(declarator)) => match &declarator.name {
+            ast_view::Pat::Ident(ident) => ident.id.sym().to_lowercase().contains("routes"),
+            _ => false,
+        },
+        Some(Node::KeyValueProp(kv)) => match prop_name(&kv.key) {
+            Some((key, _)) => ROUTES_KEYS.contains(&key.as_str()),
+            None => false,
+        },
+        _ => false,
+    }
+}
+
+/**
+ * 路由数组所属的父路由，顶层的路由数组返回 None
+ * vue-router 的 `createRouter({ routes: [...] })` 中，routes 所在的对象不是路由
+ */
+pub fn parent_route<'a>(array_lit: &ast_view::ArrayLit<'a>) -> Option<&'a ast_view::ObjectLit<'a>> {
+    match unwrap_parent(array_lit.into()) {
+        Some(Node::KeyValueProp(kv)) if is_route_object(kv.parent()) => Some(kv.parent()),
+        _ => None,
+    }
+}
+
+/**
+ * 获取对象上某个 key 的值
+ */
+pub fn prop_value<'a>(
+    object_lit: &ast_view::ObjectLit<'a>,
+    key: &str,
+) -> Option<ast_view::Expr<'a>> {
+    object_lit.props.iter().find_map(|prop| match prop {
+        ast_view::PropOrSpread::Prop(ast_view::Prop::KeyValue(kv)) => match prop_name(&kv.key) {
+            Some((name, _)) if name == key => Some(kv.value),
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+pub fn has_prop(object_lit: &ast_view::ObjectLit, key: &str) -> bool {
+    object_lit
+        .props
+        .iter()
+        .filter_map(prop_key)
+        .any(|(name, _)| name == key)
+}
+
+/**
+ * 获取静态字符串的值，包括不带表达式的模板字符串
+ */
+pub fn static_string(expr: &ast_view::Expr) -> Option<String> {
+    match expr {
+        ast_view::Expr::Lit(ast_view::Lit::Str(str)) => Some(str.value().to_string()),
+        ast_view::Expr::Tpl(tpl) if tpl.exprs.is_empty() => tpl
+            .quasis
+            .first()
+            .map(|quasi| quasi.inner.raw.value.to_string()),
+        ast_view::Expr::Paren(paren) => static_string(&paren.expr),
+        ast_view::Expr::TsAs(ts_as) => static_string(&ts_as.expr),
+        _ => None,
+    }
+}
+
+/**
+ * 把字面量转化为 JSON 的值，标识符、函数调用之类无法静态确定的值返回 None
+ */
+pub fn static_value(expr: &ast_view::Expr) -> Option<serde_json::Value> {
+    use ast_view::{Expr, Lit};
+    match expr {
+        Expr::Lit(Lit::Bool(value)) => Some(serde_json::Value::Bool(value.value())),
+        Expr::Lit(Lit::Num(value)) => {
+            serde_json::Number::from_f64(value.value()).map(serde_json::Value::Number)
+        }
+        Expr::Lit(Lit::Null(_)) => Some(serde_json::Value::Null),
+        Expr::Array(array_lit) => array_lit
+            .elems
+            .iter()
+            .map(|item| match item {
+                Some(item) if item.inner.spread.is_none() => static_value(&item.expr),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .map(serde_json::Value::Array),
+        Expr::Paren(paren) => static_value(&paren.expr),
+        Expr::TsAs(ts_as) => static_value(&ts_as.expr),
+        Expr::TsConstAssertion(assertion) => static_value(&assertion.expr),
+        expr => static_string(expr).map(serde_json::Value::String),
+    }
+}
+
+/**
+ * 路由数组中直接写出来的路由对象，展开运算符和变量引用会被忽略
+ */
+pub fn route_objects<'a>(array_lit: &ast_view::ArrayLit<'a>) -> Vec<&'a ast_view::ObjectLit<'a>> {
+    array_lit
+        .elems
+        .iter()
+        .flatten()
+        .filter(|item| item.inner.spread.is_none())
+        .filter_map(|item| match item.expr {
+            ast_view::Expr::Object(object_lit) => Some(object_lit),
+            _ => None,
+        })
+        .collect()
+}
+
+/**
+ * 判断一个对象是不是路由配置，也就是路由数组里的元素
+ */
+pub fn is_route_object(object_lit: &ast_view::ObjectLit) -> bool {
+    match unwrap_parent(object_lit.into()) {
+        Some(Node::ExprOrSpread(item)) => match unwrap_parent(item.into()) {
+            Some(Node::ArrayLit(array_lit)) => is_routes_array(array_lit),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/**
+ * 推断字面量的值类型，标识符、函数调用之类无法静态确定的值返回 None
+ */
+pub fn value_kind(expr: &ast_view::Expr) -> Option<ValueKind> {
+    use ast_view::{Expr, Lit};
+    match expr {
+        Expr::Lit(Lit::Str(_)) => Some(ValueKind::String),
+        Expr::Lit(Lit::Bool(_)) => Some(ValueKind::Boolean),
+        Expr::Lit(Lit::Num(_)) => Some(ValueKind::Number),
+        Expr::Lit(Lit::Null(_)) => Some(ValueKind::Null),
+        Expr::Tpl(tpl) if tpl.exprs.is_empty() => Some(ValueKind::String),
+        Expr::Tpl(_) => Some(ValueKind::Template),
+        Expr::Object(_) => Some(ValueKind::Object),
+        Expr::Arrow(_) | Expr::Fn(_) => Some(ValueKind::Function),
+        Expr::JSXElement(_) | Expr::JSXFragment(_) => Some(ValueKind::Element),
+        Expr::Array(array_lit) => {
+            // 只要有一个元素确定不是字符串，就是普通数组
+            let has_non_string = array_lit.elems.iter().flatten().any(|item| {
+                item.inner.spread.is_none()
+                    && !matches!(
+                        value_kind(&item.expr),
+                        None | Some(ValueKind::String) | Some(ValueKind::Template)
+                    )
+            });
+            if has_non_string {
+                Some(ValueKind::Array)
+            } else {
+                Some(ValueKind::StringArray)
+            }
+        }
+        Expr::Paren(paren) => value_kind(&paren.expr),
+        Expr::TsAs(ts_as) => value_kind(&ts_as.expr),
+        Expr::TsConstAssertion(assertion) => value_kind(&assertion.expr),
+        _ => None,
+    }
+}
+
+/**
+ * 把子路由的 path 拼接到父路由上，`./login` 和 `login` 都是相对路径
+ */
+pub fn resolve_path(parent_path: &str, path: &str) -> String {
+    if path.starts_with('/') {
+        return path.to_string();
+    }
+    let relative = path.trim_start_matches("./");
+    if relative.is_empty() || relative == "." {
+        return parent_path.to_string();
+    }
+    format!("{}/{}", parent_path.trim_end_matches('/'), relative)
+}
+
+/// 从源码中解析出来的一个路由
+pub struct Route<'a> {
+    pub object: &'a ast_view::ObjectLit<'a>,
+    /// 配置里写的 path，没有配置时为 None
+    pub path: Option<String>,
+    /// 拼接上父路由之后的完整路径，没有 path 的路由继承父路由的路径
+    pub full_path: String,
+    /// 路由所在的层级，顶层为 0
+    pub depth: usize,
+    /// react-router 的 `index: true`，匹配父路由本身的路径
+    pub index: bool,
+    /// vue-router 的 meta，菜单相关的属性通常写在这里
+    pub meta: Option<&'a ast_view::ObjectLit<'a>>,
+    pub routes: Vec<Route<'a>>,
+}
+
+/// 遍历语法树时所在的路由，进入路由对象时根据父路由计算出来
+#[derive(Clone)]
+pub struct RouteFrame<'a> {
+    pub object: &'a ast_view::ObjectLit<'a>,
+    /// 拼接上父路由之后的完整路径，没有 path 的路由继承父路由的路径
+    pub full_path: String,
+    /// 路由所在的层级，顶层为 0
+    pub depth: usize,
+    /// 自己或者最近的父路由上配置的 access
+    pub access: Option<ast_view::Expr<'a>>,
+    /// 自己或者最近的父路由上配置的 layout
+    pub layout: Option<ast_view::Expr<'a>>,
+}
+
+impl<'a> RouteFrame<'a> {
+    pub fn new(object: &'a ast_view::ObjectLit<'a>, parent: Option<&RouteFrame<'a>>) -> Self {
+        let meta = match prop_value(object, "meta") {
+            Some(ast_view::Expr::Object(meta)) => Some(meta),
+            _ => None,
+        };
+        let field = |key: &str| {
+            prop_value(object, key).or_else(|| meta.and_then(|meta| prop_value(meta, key)))
+        };
+
+        let parent_path = parent.map_or("/", |parent| parent.full_path.as_str());
+        let full_path = match prop_value(object, "path").and_then(|path| static_string(&path)) {
+            Some(path) => resolve_path(parent_path, &path),
+            None => parent_path.to_string(),
+        };
+        RouteFrame {
+            object,
+            full_path,
+            depth: parent.map_or(0, |parent| parent.depth + 1),
+            access: field("access").or_else(|| parent.and_then(|parent| parent.access)),
+            layout: field("layout").or_else(|| parent.and_then(|parent| parent.layout)),
+        }
+    }
+}
+
+/// 一个顶层路由数组以及它下面所有的子路由
+pub struct RouteTree<'a> {
+    pub routes: Vec<Route<'a>>,
+}
+
+impl<'a> Route<'a> {
+    /**
+     * 获取路由上的属性，路由上没有时再从 meta 中查找
+     */
+    pub fn field(&self, key: &str) -> Option<ast_view::Expr<'a>> {
+        prop_value(self.object, key).or_else(|| self.meta.and_then(|meta| prop_value(meta, key)))
+    }
+
+    /**
+     * 路由上所有的 key: value 属性，包括 meta 中的属性，路由上的属性覆盖 meta 中的同名属性
+     */
+    pub fn fields(&self) -> BTreeMap<String, ast_view::Expr<'a>> {
+        let mut fields = BTreeMap::new();
+        for object in self.meta.iter().chain(std::iter::once(&self.object)) {
+            for prop in object.props.iter() {
+                if let ast_view::PropOrSpread::Prop(ast_view::Prop::KeyValue(kv)) = prop {
+                    if let Some((key, _)) = prop_name(&kv.key) {
+                        fields.insert(key, kv.value);
+                    }
+                }
+            }
+        }
+        fields
+    }
+
+    /// 包括自己在内，深度优先遍历所有的路由
+    pub fn walk(&self, visit: &mut impl FnMut(&Route<'a>)) {
+        visit(self);
+        for route in &self.routes {
+            route.walk(visit);
+        }
+    }
+}
+
+impl<'a> RouteTree<'a> {
+    /**
+     * 找到源码中所有的顶层路由数组，并解析成路由树
+     */
+    pub fn from_program(
+        program: ast_view::Program<'a>,
+        framework: Framework,
+    ) -> Vec<RouteTree<'a>> {
+        let mut trees = vec![];
+        collect_root_arrays(program.into(), framework, &mut trees);
+        trees
+    }
+
+    pub fn walk(&self, visit: &mut impl FnMut(&Route<'a>)) {
+        for route in &self.routes {
+            route.walk(visit);
+        }
+    }
+}
+
+fn collect_root_arrays<'a>(node: Node<'a>, framework: Framework, trees: &mut Vec<RouteTree<'a>>) {
+    if let Node::ArrayLit(array) = node {
+        if is_routes_array(array) && parent_route(array).is_none() {
+            trees.push(RouteTree {
+                routes: build_routes(array, framework, "/", 0),
+            });
+            return;
+        }
+    }
+    for child in node.children() {
+        collect_root_arrays(child, framework, trees);
+    }
+}
+
+fn build_routes<'a>(
+    array_lit: &'a ast_view::ArrayLit<'a>,
+    framework: Framework,
+    parent_path: &str,
+    depth: usize,
+) -> Vec<Route<'a>> {
+    route_objects(array_lit)
+        .into_iter()
+        .map(|object| {
+            let path = prop_value(object, "path").and_then(|path| static_string(&path));
+            let full_path = match &path {
+                Some(path) => resolve_path(parent_path, path),
+                None => parent_path.to_string(),
+            };
+            let routes = framework
+                .children_keys()
+                .iter()
+                .find_map(|key| match prop_value(object, key) {
+                    Some(ast_view::Expr::Array(array)) => {
+                        Some(build_routes(array, framework, &full_path, depth + 1))
+                    }
+                    _ => None,
+                })
+                .unwrap_or_default();
+            let index = matches!(
+                prop_value(object, "index"),
+                Some(ast_view::Expr::Lit(ast_view::Lit::Bool(value))) if value.value()
+            );
+            let meta = match prop_value(object, "meta") {
+                Some(ast_view::Expr::Object(meta)) => Some(meta),
+                _ => None,
+            };
+            Route {
+                object,
+                path,
+                full_path,
+                depth,
+                index,
+                meta,
+                routes,
+            }
+        })
+        .collect()
+}
+
+/**
+ * 跳过括号和 `as`，返回真正的表达式
+ */
+fn unwrap_expr<'a>(expr: &ast_view::Expr<'a>) -> ast_view::Expr<'a> {
+    match expr {
+        ast_view::Expr::Paren(paren) => unwrap_expr(&paren.expr),
+        ast_view::Expr::TsAs(ts_as) => unwrap_expr(&ts_as.expr),
+        ast_view::Expr::TsConstAssertion(assertion) => unwrap_expr(&assertion.expr),
+        ast_view::Expr::TsTypeAssertion(assertion) => unwrap_expr(&assertion.expr),
+        _ => *expr,
+    }
+}
+
+/**
+ * umi 的配置对象：`export default defineConfig({ ... })` 或者直接默认导出的对象
+ */
+fn config_object<'a>(module: &ast_view::Module<'a>) -> Option<&'a ast_view::ObjectLit<'a>> {
+    module.body.iter().find_map(|item| match item {
+        ast_view::ModuleItem::ModuleDecl(ast_view::ModuleDecl::ExportDefaultExpr(export)) => {
+            match unwrap_expr(&export.expr) {
+                ast_view::Expr::Object(object_lit) => Some(object_lit),
+                ast_view::Expr::Call(call) => match &call.callee {
+                    ast_view::ExprOrSuper::Expr(ast_view::Expr::Ident(ident))
+                        if ident.sym().as_ref() == "defineConfig" =>
+                    {
+                        match call.args.first().map(|arg| unwrap_expr(&arg.expr)) {
+                            Some(ast_view::Expr::Object(object_lit)) => Some(object_lit),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                },
+                _ => None,
+            }
+        }
+        _ => None,
+    })
+}
+
+/**
+ * 配置对象的 routes 中引用的变量名，包括 `routes`、`routes: routes`
+ * 以及 `routes: [...userRoutes, ...adminRoutes]` 中展开的变量
+ */
+fn config_routes_idents(config: &ast_view::ObjectLit) -> Vec<String> {
+    let mut idents = vec![];
+    for prop in &config.props {
+        match prop {
+            ast_view::PropOrSpread::Prop(ast_view::Prop::Shorthand(ident))
+                if ident.sym().as_ref() == "routes" =>
+            {
+                idents.push(ident.sym().to_string());
+            }
+            ast_view::PropOrSpread::Prop(ast_view::Prop::KeyValue(kv)) if matches!(prop_name(&kv.key), Some((key, _)) if key == "routes") => {
+                match unwrap_expr(&kv.value) {
+                    ast_view::Expr::Ident(ident) => idents.push(ident.sym().to_string()),
+                    ast_view::Expr::Array(array_lit) => {
+                        for item in array_lit.elems.iter().flatten() {
+                            if let (Some(_), ast_view::Expr::Ident(ident)) =
+                                (item.inner.spread, unwrap_expr(&item.expr))
+                            {
+                                idents.push(ident.sym().to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+    }
+    idents
+}
+
+/**
+ * 配置文件中 routes 引用的模块，比如 `import routes from './routes'` 返回 `./routes`
+ * 这些模块需要单独检查，错误的位置才能对应到路由文件本身
+ */
+pub fn imported_routes(program: ast_view::Program) -> Vec<String> {
+    let module = match program {
+        ast_view::Program::Module(module) => module,
+        ast_view::Program::Script(_) => return vec![],
+    };
+    let idents = match config_object(module) {
+        Some(config) => config_routes_idents(config),
+        None => return vec![],
+    };
+
+    let mut sources: Vec<String> = vec![];
+    for item in &module.body {
+        if let ast_view::ModuleItem::ModuleDecl(ast_view::ModuleDecl::Import(import)) = item {
+            let imported = import.specifiers.iter().any(|specifier| {
+                let local = match specifier {
+                    ast_view::ImportSpecifier::Default(default) => default.local,
+                    ast_view::ImportSpecifier::Named(named) => named.local,
+                    ast_view::ImportSpecifier::Namespace(_) => return false,
+                };
+                idents.iter().any(|ident| ident == local.sym().as_ref())
+            });
+            let source = import.src.value().to_string();
+            if imported && !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+    }
+    sources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn imports(source: &str) -> Vec<String> {
+        crate::parse_program("config.ts", source.to_string())
+            .unwrap()
+            .with_view(imported_routes)
+    }
+
+    #[test]
+    fn finds_routes_in_define_config() {
+        let source = "import { defineConfig } from 'umi';
+import routes from './routes';
+import theme from './theme';
+export default defineConfig({ routes, theme });";
+        assert_eq!(imports(source), ["./routes"]);
+    }
+
+    #[test]
+    fn finds_routes_in_default_exported_object() {
+        let source = "import routes from './routes';\nexport default { routes };";
+        assert_eq!(imports(source), ["./routes"]);
+    }
+
+    #[test]
+    fn finds_renamed_and_spread_routes() {
+        let source = "import importedRoutes from './routes';
+import { adminRoutes } from './admin';
+import userRoutes from './user';
+export default { routes: importedRoutes };";
+        assert_eq!(imports(source), ["./routes"]);
+
+        let source = "import { adminRoutes } from './admin';
+import userRoutes from './user';
+export default { routes: [...adminRoutes, ...userRoutes, { path: '/' }] };";
+        assert_eq!(imports(source), ["./admin", "./user"]);
+    }
+
+    #[test]
+    fn ignores_files_without_config() {
+        assert!(imports("import routes from './routes';\nexport default routes;").is_empty());
+        assert!(imports("export default [{ path: '/' }];").is_empty());
+    }
+}
@@ -0,0 +1,93 @@
+use crate::config::Framework;
+use crate::diagnostic::{Position, Range};
+use crate::route::{static_value, Route, RouteTree};
+use deno_ast::swc::common::{Span, Spanned};
+use deno_ast::view::NodeTrait;
+use deno_ast::{ParsedSource, SourceTextInfo};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// 从源码中解析出来的一个路由，可以直接序列化成 JSON
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteInfo {
+    /// 配置里写的 path，没有配置或者不是字符串时为 None
+    pub path: Option<String>,
+    /// 拼接上父路由之后的完整路径
+    pub full_path: String,
+    /// 路由所在的层级，顶层为 0
+    pub depth: usize,
+    pub index: bool,
+    /// 路由对象的范围
+    pub range: Range,
+    /// 路由上的属性，包括 vue-router 的 meta 中的属性
+    pub fields: BTreeMap<String, FieldInfo>,
+    pub routes: Vec<RouteInfo>,
+}
+
+/// 路由上的一个属性
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldInfo {
+    /// 静态求出的值，变量之类无法确定的值为 null
+    pub value: Option<Value>,
+    /// 属性值的源码
+    pub text: String,
+    /// 属性值的范围
+    pub range: Range,
+}
+
+/**
+ * 源码中所有的顶层路由，路由的识别方式和规则一致
+ */
+pub fn route_info(parsed_source: &ParsedSource, framework: Framework) -> Vec<RouteInfo> {
+    let source = parsed_source.source();
+    parsed_source.with_view(|program| {
+        RouteTree::from_program(program, framework)
+            .iter()
+            .flat_map(|tree| tree.routes.iter().map(|route| to_route_info(route, source)))
+            .collect()
+    })
+}
+
+fn to_route_info(route: &Route, source: &SourceTextInfo) -> RouteInfo {
+    let fields = route
+        .fields()
+        .into_iter()
+        .map(|(key, value)| {
+            let field = FieldInfo {
+                value: static_value(&value),
+                text: value.text().to_string(),
+                range: range(value.span(), source),
+            };
+            (key, field)
+        })
+        .collect();
+
+    RouteInfo {
+        path: route.path.clone(),
+        full_path: route.full_path.clone(),
+        depth: route.depth,
+        index: route.index,
+        range: range(route.object.span(), source),
+        fields,
+        routes: route
+            .routes
+            .iter()
+            .map(|route| to_route_info(route, source))
+            .collect(),
+    }
+}
+
+fn range(span: Span, source: &SourceTextInfo) -> Range {
+    Range {
+        start: Position::new(span.lo, source.line_and_column_index(span.lo)),
+        end: Position::new(span.hi, source.line_and_column_index(span.hi)),
+    }
+}
+
+impl RouteInfo {
+    pub fn field(&self, key: &str) -> Option<&FieldInfo> {
+        self.fields.get(key)
+    }
+}
@@ -1,15 +1,24 @@
-﻿use deno_ast::view::swc_ast;
+use deno_ast::view::swc_ast;
 use deno_ast::view::Program;
 
 pub mod children_key;
+pub mod custom;
+pub mod empty_route;
+pub mod nested_path;
 pub mod redirect;
 pub mod repeat;
+pub mod repeat_name;
+pub mod require_404;
+#[cfg(test)]
+mod test_util;
+pub mod unknown_key;
+pub mod value_type;
 
+use crate::config::{Config, Framework};
 use crate::context::Context;
+use crate::handler::{Dispatcher, Handler};
 use std::sync::Arc;
 
-#[derive()]
-
 pub enum ProgramRef<'a> {
     Module(&'a swc_ast::Module),
     Script(&'a swc_ast::Script),
@@ -19,15 +28,41 @@ pub trait LintRule: std::fmt::Debug + Send + Sync {
     /// Returns the unique code that identifies the rule
     fn code(&self) -> &'static str;
 
-    fn lint_program<'view>(&self, context: &mut Context<'view>, program: ProgramRef<'view>);
+    /// Returns the tags this rule belongs to, e.g. `recommended`
+    fn tags(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Returns the frameworks whose route configs this rule understands
+    fn frameworks(&self) -> &'static [Framework] {
+        &[Framework::Umi, Framework::ReactRouter, Framework::VueRouter]
+    }
+
+    /// Lints the raw swc program. Rules that use a handler or override
+    /// `lint_program_with_ast_view` don't need to implement this.
+    fn lint_program<'view>(&self, _context: &mut Context<'view>, _program: ProgramRef<'view>) {}
+
+    /// Returns the handler of this rule, so that all rules can share a single
+    /// traversal. Rules that walk the program on their own return `None`.
+    fn handler(&self, _context: &Context) -> Option<Box<dyn Handler>> {
+        None
+    }
 
     /// Executes lint using `dprint-swc-ecma-ast-view`.
-    /// Falls back to the `lint_program` method if not implemented.
+    /// Traverses with the rule's handler if there is one, and falls back to the
+    /// `lint_program` method otherwise.
     fn lint_program_with_ast_view<'view>(
         &self,
         context: &mut Context<'view>,
         program: Program<'view>,
     ) {
+        if let Some(mut handler) = self.handler(context) {
+            let mut dispatcher = Dispatcher::default();
+            dispatcher.add(handler.as_mut());
+            dispatcher.traverse(program, context);
+            return;
+        }
+
         use Program::*;
         let program_ref = match program {
             Module(m) => ProgramRef::Module(m.inner),
@@ -35,11 +70,6 @@ pub trait LintRule: std::fmt::Debug + Send + Sync {
         };
         self.lint_program(context, program_ref);
     }
-
-    /// Creates an instance of this rule.
-    fn new() -> Arc<Self>
-    where
-        Self: Sized;
 }
 
 pub fn get_all_rules_raw() -> Vec<Arc<dyn LintRule>> {
@@ -47,5 +77,155 @@ pub fn get_all_rules_raw() -> Vec<Arc<dyn LintRule>> {
         children_key::ChildrenKey::new(),
         redirect::RedirectKeys::new(),
         repeat::RepeatPath::new(),
+        unknown_key::UnknownKey::new(),
+        value_type::ValueType::new(),
+        require_404::Require404::new(),
+        empty_route::EmptyRoute::new(),
+        nested_path::NestedPath::new(),
+        repeat_name::RepeatName::new(),
     ]
 }
+
+/// 重复 path 的规则以前和 redirect 规则共用同一个 code，旧的配置仍然对两个规则生效
+const LEGACY_CODES: &[(&str, &str)] = &[("redirect-only-has-redirect-and-path", "no-repeat-path")];
+
+/// 配置中的 code 是否指向这个规则
+fn is_listed(codes: &[String], rule: &dyn LintRule) -> bool {
+    codes.iter().any(|code| {
+        code == rule.code()
+            || LEGACY_CODES
+                .iter()
+                .any(|(legacy, current)| code == legacy && *current == rule.code())
+    })
+}
+
+/**
+ * 选出当前框架可用的规则，在推荐规则的基础上根据配置开启或者关闭规则
+ */
+pub fn get_filtered_rules(config: &Config) -> Vec<Arc<dyn LintRule>> {
+    let rules = &config.rules;
+    get_all_rules_raw()
+        .into_iter()
+        .filter(|rule| rule.frameworks().contains(&config.framework))
+        .filter(|rule| rule.tags().contains(&"recommended") || is_listed(&rules.include, &**rule))
+        .filter(|rule| !is_listed(&rules.exclude, &**rule))
+        .chain(plugin_rules(config))
+        .collect()
+}
+
+/**
+ * 配置中的 .wasm 插件规则，关闭的插件同样通过 rules.exclude 过滤
+ */
+#[cfg(feature = "plugins")]
+fn plugin_rules(config: &Config) -> Vec<Arc<dyn LintRule>> {
+    crate::plugin::plugin_rules(config)
+        .into_iter()
+        .filter(|rule| !is_listed(&config.rules.exclude, &**rule))
+        .collect()
+}
+
+/// 没有开启 plugins feature 时忽略配置中的插件
+#[cfg(not(feature = "plugins"))]
+fn plugin_rules(_config: &Config) -> Vec<Arc<dyn LintRule>> {
+    vec![]
+}
+
+/**
+ * 执行一组规则，再执行配置中的自定义规则
+ */
+pub fn lint_program<'view>(
+    context: &mut Context<'view>,
+    program: Program<'view>,
+    rules: &[Arc<dyn LintRule>],
+) {
+    dispatch_rules(context, program, rules);
+    custom::lint_custom_rules(context, program);
+}
+
+/**
+ * 提供了 handler 的规则合并成一次遍历，其余的规则单独执行
+ * 错误按照规则的顺序排列，和每个规则单独遍历时的结果一致
+ */
+pub fn dispatch_rules<'view>(
+    context: &mut Context<'view>,
+    program: Program<'view>,
+    rules: &[Arc<dyn LintRule>],
+) {
+    let mut handlers = Vec::new();
+    for rule in rules {
+        match rule.handler(context) {
+            Some(handler) => handlers.push(handler),
+            None => rule.lint_program_with_ast_view(context, program),
+        }
+    }
+    let mut dispatcher = Dispatcher::default();
+    for handler in &mut handlers {
+        dispatcher.add(handler.as_mut());
+    }
+    if !dispatcher.is_empty() {
+        dispatcher.traverse(program, context);
+    }
+
+    let order = |code: &str| rules.iter().position(|rule| rule.code() == code);
+    context.sort_diagnostics_by_key(|diagnostic| order(&diagnostic.code));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostic::LintDiagnostic;
+    use std::collections::BTreeSet;
+
+    /// 每个内置规则都至少会报一个问题
+    const SOURCE: &str = "export default [
+  { path: '/a', name: 'a', children: [{ path: '/b', component: './B' }] },
+  { path: '/a', name: 'a', component: './A' },
+  { path: '/c', redirect: '/a', component: './C' },
+  { path: '/d', exact: 1, component: './D' },
+  { path: '/e', unknown: true, component: './E' },
+  { path: '/f', name: 'f' },
+];";
+
+    /**
+     * 开启所有内置规则检查 SOURCE，`dispatch` 为 false 时每个规则单独遍历
+     */
+    fn lint(dispatch: bool) -> Vec<LintDiagnostic> {
+        let config = Config::default();
+        let rules = get_all_rules_raw();
+        let ast = crate::parse_program("routes.ts", SOURCE.to_string()).unwrap();
+        ast.with_view(|program| {
+            let mut context = Context::new(
+                "routes.ts".to_string(),
+                ast.media_type(),
+                &config,
+                ast.source(),
+                program,
+            );
+            if dispatch {
+                dispatch_rules(&mut context, program, &rules);
+            } else {
+                for rule in &rules {
+                    rule.lint_program_with_ast_view(&mut context, program);
+                }
+            }
+            context.diagnostics().to_vec()
+        })
+    }
+
+    #[test]
+    fn dispatch_matches_linting_each_rule_alone() {
+        let per_rule = lint(false);
+        let dispatched = lint(true);
+
+        let codes: BTreeSet<_> = per_rule
+            .iter()
+            .map(|diagnostic| diagnostic.code.as_str())
+            .collect();
+        let all: BTreeSet<_> = get_all_rules_raw().iter().map(|rule| rule.code()).collect();
+        assert_eq!(codes, all);
+        assert_eq!(
+            serde_json::to_value(&dispatched).unwrap(),
+            serde_json::to_value(&per_rule).unwrap()
+        );
+    }
+}
@@ -1,10 +1,11 @@
-﻿use crate::handler::{Handler, Traverse};
+use crate::diagnostic::{LintFix, LintFixChange};
+use crate::handler::Handler;
+use crate::route::{has_prop, prop_key};
+use crate::selector::{Captures, Query};
 
-use super::{Context, LintRule, Program, ProgramRef};
-use deno_ast::{
-    swc::common::Spanned,
-    view::{self as ast_view, NodeTrait},
-};
+use super::{Context, LintRule};
+use crate::config::Framework;
+use deno_ast::{swc::common::Spanned, view as ast_view};
 use std::sync::Arc;
 
 const MESSAGE: &str =
@@ -20,30 +21,131 @@ impl LintRule for ChildrenKey {
         CODE
     }
 
-    fn lint_program<'view>(&self, _context: &mut Context<'view>, _program: ProgramRef<'view>) {
-        unreachable!();
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
     }
 
-    fn lint_program_with_ast_view(&self, context: &mut Context, program: Program<'_>) {
-        ChildrenKeyHandler.traverse(program, context);
+    fn frameworks(&self) -> &'static [Framework] {
+        // react-router 和 vue-router 中的写法不一样
+        &[Framework::Umi]
     }
 
-    fn new() -> Arc<Self> {
+    fn handler(&self, _context: &Context) -> Option<Box<dyn Handler>> {
+        // 选择器是写死的，selector_compiles 测试保证可以解析
+        let query = Query::new()
+            .on(SELECTOR, |_, captures, ctx| {
+                check_children_key(captures, ctx)
+            })
+            .expect("valid selector")
+            .skip_non_route();
+        Some(Box::new(query))
+    }
+}
+
+impl ChildrenKey {
+    pub fn new() -> Arc<Self> {
         Arc::new(ChildrenKey)
     }
 }
 
-struct ChildrenKeyHandler;
-
-impl Handler for ChildrenKeyHandler {
-    fn object_lit(&mut self, object_lit: &ast_view::ObjectLit, ctx: &mut Context) {
-        let obj_keys: Vec<String> = object_lit
-            .children()
-            .iter()
-            .map(|obj_name| obj_name.children()[0].text().to_string())
-            .collect();
-        if obj_keys.contains(&String::from("children")) {
-            ctx.add_diagnostic(object_lit.span(), CODE, MESSAGE);
+/// 对象上的 children 属性，包括简写的 `{ children }`
+const SELECTOR: &str =
+    "ObjectLit@object > KeyValueProp[key=children], ObjectLit@object > Ident[key=children]";
+
+fn check_children_key(captures: &Captures, ctx: &mut Context) {
+    let object_lit = match captures.get("object") {
+        Some(ast_view::Node::ObjectLit(object_lit)) => object_lit,
+        _ => return,
+    };
+    // 已经有 routes 的时候不能直接改名，需要手动合并
+    if has_prop(object_lit, "routes") {
+        ctx.add_diagnostic(object_lit.span(), CODE, MESSAGE);
+        return;
+    }
+
+    let fixes = object_lit
+        .props
+        .iter()
+        .find_map(|prop| children_fix(prop, ctx))
+        .into_iter()
+        .collect();
+    ctx.add_diagnostic_with_fixes(object_lit.span(), CODE, MESSAGE, None, fixes);
+}
+
+/**
+ * 简写的 `{ children }` 改为 `{ routes: children }`，变量名保持不变
+ * 其余的只替换 key，带引号的 key 保留原来的引号
+ */
+fn children_fix(prop: &ast_view::PropOrSpread, ctx: &Context) -> Option<LintFix> {
+    let (key, span) = prop_key(prop)?;
+    if key != "children" {
+        return None;
+    }
+    let new_text = match prop {
+        ast_view::PropOrSpread::Prop(ast_view::Prop::Shorthand(_)) => {
+            "routes: children".to_string()
         }
+        _ => ctx.file_text_substring(&span).replace("children", "routes"),
+    };
+    Some(LintFix {
+        description: "把 children 改为 routes".to_string(),
+        changes: vec![LintFixChange {
+            new_text,
+            range: ctx.range(span),
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CODE, MESSAGE, SELECTOR};
+    use crate::rules::test_util::{fix, lint};
+    use crate::selector::Selector;
+
+    #[test]
+    fn selector_compiles() {
+        assert!(Selector::parse(SELECTOR).is_ok());
+    }
+
+    #[test]
+    fn allows_routes() {
+        let source = "export default [{ path: '/a', routes: [{ path: 'b', component: './B' }] }];";
+        assert!(lint(CODE, source).is_empty());
+    }
+
+    #[test]
+    fn renames_key_value_children() {
+        let source =
+            "export default [{ path: '/a', children: [{ path: 'b', component: './B' }] }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, MESSAGE);
+        assert_eq!(
+            fix(CODE, source),
+            "export default [{ path: '/a', routes: [{ path: 'b', component: './B' }] }];"
+        );
+
+        let source = "export default [{ path: '/a', 'children': children }];";
+        assert_eq!(
+            fix(CODE, source),
+            "export default [{ path: '/a', 'routes': children }];"
+        );
+    }
+
+    #[test]
+    fn keeps_shorthand_value() {
+        let source = "const children = [];\nexport default [{ path: '/a', children }];";
+        assert_eq!(
+            fix(CODE, source),
+            "const children = [];\nexport default [{ path: '/a', routes: children }];"
+        );
+    }
+
+    #[test]
+    fn does_not_fix_when_routes_exists() {
+        let source = "export default [{ path: '/a', routes: [], children: [] }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].fixes.is_empty());
     }
 }
@@ -0,0 +1,289 @@
+use crate::config::{CustomRuleConfig, FieldCondition};
+use crate::context::Context;
+use crate::route::{static_value, Route, RouteTree};
+use deno_ast::swc::common::Spanned;
+use deno_ast::view::{self as ast_view, Program};
+use serde_json::Value;
+
+/**
+ * 执行配置文件中的自定义规则，规则按照配置的顺序执行
+ * rules.exclude 中的规则会被跳过
+ */
+pub fn lint_custom_rules<'view>(context: &mut Context<'view>, program: Program<'view>) {
+    let config = context.config();
+    let rules: Vec<&CustomRuleConfig> = config
+        .custom_rules
+        .iter()
+        .filter(|rule| !config.rules.exclude.contains(&rule.code))
+        .collect();
+    if rules.is_empty() {
+        return;
+    }
+
+    let trees = RouteTree::from_program(program, config.framework);
+    for rule in rules {
+        for tree in &trees {
+            tree.walk(&mut |route| check_route(rule, route, context));
+        }
+    }
+}
+
+fn check_route(rule: &CustomRuleConfig, route: &Route, context: &mut Context) {
+    let matcher = &rule.matcher;
+    if !matcher.path.is_empty()
+        && !matcher
+            .path
+            .iter()
+            .any(|pattern| glob_match(pattern, &route.full_path))
+    {
+        return;
+    }
+    // 无法静态确定的值不算匹配
+    let matched = matcher
+        .fields
+        .iter()
+        .all(|(field, condition)| test(condition, route.field(field)) == Some(true));
+    if !matched {
+        return;
+    }
+
+    // 无法静态确定的值不报错，每个路由只报第一个不满足的属性
+    let failed = rule
+        .assert
+        .iter()
+        .find(|(field, condition)| test(condition, route.field(field)) == Some(false));
+    if let Some((field, condition)) = failed {
+        let span = match route.field(field) {
+            Some(value) => value.span(),
+            None => route.object.span(),
+        };
+        let hint = rule.hint.clone().unwrap_or_else(|| {
+            format!(
+                "路由 `{}` 的 `{}` {}",
+                route.full_path,
+                field,
+                describe(condition)
+            )
+        });
+        context.add_diagnostic_with_severity(
+            span,
+            &rule.code,
+            &rule.message,
+            Some(hint),
+            rule.severity,
+        );
+    }
+}
+
+/**
+ * 判断属性是否满足条件，值无法静态确定时返回 None
+ */
+fn test(condition: &FieldCondition, value: Option<ast_view::Expr>) -> Option<bool> {
+    if let Some(exists) = condition.exists {
+        if value.is_some() != exists {
+            return Some(false);
+        }
+    }
+    if condition.equals.is_none() && condition.includes.is_none() && condition.matches.is_none() {
+        return Some(true);
+    }
+    let value = match value {
+        Some(value) => static_value(&value)?,
+        None => return Some(false),
+    };
+
+    if let Some(expected) = &condition.equals {
+        if !value_eq(&value, expected) {
+            return Some(false);
+        }
+    }
+    if let Some(expected) = &condition.includes {
+        match &value {
+            Value::Array(items) if items.iter().any(|item| value_eq(item, expected)) => {}
+            _ => return Some(false),
+        }
+    }
+    if let Some(pattern) = &condition.matches {
+        match &value {
+            Value::String(value) if glob_match(pattern, value) => {}
+            _ => return Some(false),
+        }
+    }
+    Some(true)
+}
+
+fn describe(condition: &FieldCondition) -> String {
+    let mut parts = vec![];
+    match condition.exists {
+        Some(true) => parts.push("需要配置".to_string()),
+        Some(false) => parts.push("不能配置".to_string()),
+        None => {}
+    }
+    if let Some(expected) = &condition.equals {
+        parts.push(format!("应该等于 {}", expected));
+    }
+    if let Some(expected) = &condition.includes {
+        parts.push(format!("应该包含 {}", expected));
+    }
+    if let Some(pattern) = &condition.matches {
+        parts.push(format!("应该匹配 `{}`", pattern));
+    }
+    parts.join("，")
+}
+
+/// 数字统一按照 f64 比较，配置中的 `1` 和源码中的 `1` 是相等的
+fn value_eq(actual: &Value, expected: &Value) -> bool {
+    match (actual, expected) {
+        (Value::Number(actual), Value::Number(expected)) => actual.as_f64() == expected.as_f64(),
+        (actual, expected) => actual == expected,
+    }
+}
+
+/**
+ * 按照 `/` 分段匹配，`**` 匹配任意多段（包括零段），`*` 和 `?` 只在一段之内匹配
+ * 结尾的 `**` 可以匹配零段，所以 `/settings` 本身也会被匹配到
+ */
+fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<&str> = pattern.split('/').filter(|part| !part.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    match_segments(&pattern, &path)
+}
+
+fn match_segments(pattern: &[&str], path: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => (0..=path.len()).any(|skip| match_segments(rest, &path[skip..])),
+        Some((part, rest)) => match path.split_first() {
+            Some((segment, path)) => {
+                match_segment(part.as_bytes(), segment.as_bytes()) && match_segments(rest, path)
+            }
+            None => false,
+        },
+    }
+}
+
+fn match_segment(pattern: &[u8], text: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => text.is_empty(),
+        Some((b'*', rest)) => (0..=text.len()).any(|skip| match_segment(rest, &text[skip..])),
+        Some((b'?', rest)) => !text.is_empty() && match_segment(rest, &text[1..]),
+        Some((char, rest)) => text.first() == Some(char) && match_segment(rest, &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::glob_match;
+    use crate::diagnostic::Severity;
+    use crate::rules::test_util::{config, lint_with, texts};
+    use serde_json::{json, Value};
+
+    fn lint(rule: Value, source: &str) -> Vec<crate::LintDiagnostic> {
+        let mut rule = rule;
+        rule["code"] = json!("custom");
+        rule["message"] = json!("自定义规则");
+        lint_with(config(json!({ "customRules": [rule] })), "custom", source)
+    }
+
+    #[test]
+    fn matches_globs_by_segment() {
+        assert!(glob_match("/settings/**", "/settings"));
+        assert!(glob_match("/settings/**", "/settings/a/b"));
+        assert!(!glob_match("/settings/**", "/settingsx"));
+        assert!(glob_match("/**/detail", "/detail"));
+        assert!(glob_match("/**/detail", "/a/b/detail"));
+        assert!(glob_match("/users/*", "/users/:id"));
+        assert!(!glob_match("/users/*", "/users"));
+        assert!(!glob_match("/users/*", "/users/a/b"));
+        assert!(glob_match("/page-?", "/page-1"));
+        assert!(!glob_match("/page-?", "/page-10"));
+        assert!(glob_match("*.html", "index.html"));
+        // 多余的 `/` 不影响匹配
+        assert!(glob_match("/a/b", "a/b/"));
+    }
+
+    #[test]
+    fn checks_routes_matching_the_path() {
+        let rule = json!({
+            "severity": "warning",
+            "match": { "path": ["/settings/**", "/profile"] },
+            "assert": { "hideInMenu": { "equals": true } }
+        });
+        let source = "export default [
+  { path: '/settings', hideInMenu: false, routes: [{ path: 'a' }, { path: 'b', hideInMenu: true }] },
+  { path: '/profile', hideInMenu: visible },
+  { path: '/home' },
+];";
+        let diagnostics = lint(rule, source);
+        assert_eq!(texts(source, &diagnostics), ["false", "{ path: 'a' }"]);
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.severity == Severity::Warning));
+        assert_eq!(
+            diagnostics[1].hint.as_deref(),
+            Some("路由 `/settings/a` 的 `hideInMenu` 应该等于 true")
+        );
+    }
+
+    #[test]
+    fn checks_field_conditions() {
+        let rule = json!({
+            "match": { "fields": { "wrappers": { "exists": true } } },
+            "assert": {
+                "wrappers": { "includes": "@/wrappers/auth" },
+                "access": { "exists": true, "matches": "can*" }
+            }
+        });
+        let source = "export default [
+  { path: '/a', wrappers: ['@/wrappers/auth'], access: 'canA' },
+  { path: '/b', wrappers: ['@/wrappers/log'], access: 'canB' },
+  { path: '/c', wrappers: ['@/wrappers/auth'] },
+  { path: '/d', wrappers: ['@/wrappers/auth'], access: 'admin' },
+  { path: '/e' },
+  { path: '/f', wrappers, access: 'canF' },
+];";
+        let diagnostics = lint(rule, source);
+        // 每个路由只报第一个不满足的属性，不存在的属性报在路由上，无法确定的值不报错
+        assert_eq!(
+            texts(source, &diagnostics),
+            [
+                "['@/wrappers/log']",
+                "{ path: '/c', wrappers: ['@/wrappers/auth'] }",
+                "'admin'",
+            ]
+        );
+        assert_eq!(
+            diagnostics[2].hint.as_deref(),
+            Some("路由 `/d` 的 `access` 需要配置，应该匹配 `can*`")
+        );
+    }
+
+    #[test]
+    fn compares_numbers_and_uses_meta() {
+        let rule = json!({ "assert": { "order": { "equals": 1 } }, "hint": "order 必须为 1" });
+        let source = "export default [{ path: '/a', order: 1.0 }, { path: '/b', order: 2 }];";
+        let diagnostics = lint(rule, source);
+        assert_eq!(texts(source, &diagnostics), ["2"]);
+        assert_eq!(diagnostics[0].hint.as_deref(), Some("order 必须为 1"));
+
+        let rule = json!({ "assert": { "title": { "exists": true } } });
+        let vue_router = config(json!({
+            "framework": "vue-router",
+            "customRules": [{ "code": "custom", "message": "m", "assert": rule["assert"] }]
+        }));
+        let source =
+            "createRouter({ routes: [{ path: '/a', meta: { title: 'A' } }, { path: '/b' }] });";
+        let diagnostics = lint_with(vue_router, "custom", source);
+        assert_eq!(texts(source, &diagnostics), ["{ path: '/b' }"]);
+    }
+
+    #[test]
+    fn skips_excluded_rules() {
+        let config = config(json!({
+            "rules": { "exclude": ["custom"] },
+            "customRules": [{ "code": "custom", "message": "m", "assert": { "name": { "exists": true } } }]
+        }));
+        let diagnostics = lint_with(config, "custom", "export default [{ path: '/a' }];");
+        assert!(diagnostics.is_empty());
+    }
+}
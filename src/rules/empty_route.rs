@@ -0,0 +1,202 @@
+use crate::config::Framework;
+use crate::handler::{Control, Handler, NodeKindMask};
+use crate::route::{
+    has_prop, is_route_object, prop_value, route_objects, skip_non_route, NON_ROUTE_KINDS,
+};
+
+use super::{Context, LintRule};
+use deno_ast::swc::common::Spanned;
+use deno_ast::view as ast_view;
+use deno_ast::view::NodeKind;
+use std::sync::Arc;
+
+const EMPTY_ROUTES_MESSAGE: &str = "🚨 子路由是一个空数组，请删除或者补充子路由！";
+const ALL_HIDDEN_MESSAGE: &str =
+    "🚨 子路由全部配置了 hideInMenu，父路由又没有 component，菜单中点击会进入空白页面！";
+
+#[derive(Debug)]
+pub struct EmptyRoute;
+
+const CODE: &str = "no-empty-route";
+
+impl LintRule for EmptyRoute {
+    fn code(&self) -> &'static str {
+        CODE
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
+    }
+
+    fn handler(&self, context: &Context) -> Option<Box<dyn Handler>> {
+        let framework = context.config().framework;
+        Some(Box::new(EmptyRouteHandler { framework }))
+    }
+}
+
+impl EmptyRoute {
+    pub fn new() -> Arc<Self> {
+        Arc::new(EmptyRoute)
+    }
+}
+
+struct EmptyRouteHandler {
+    framework: Framework,
+}
+
+/**
+ * hideInMenu 可能直接写在路由上，也可能写在 vue-router 的 meta 中
+ */
+fn is_hidden_in_menu(route: &ast_view::ObjectLit) -> bool {
+    let hide_in_menu =
+        prop_value(route, "hideInMenu").or_else(|| match prop_value(route, "meta") {
+            Some(ast_view::Expr::Object(meta)) => prop_value(meta, "hideInMenu"),
+            _ => None,
+        });
+    matches!(
+        hide_in_menu,
+        Some(ast_view::Expr::Lit(ast_view::Lit::Bool(value))) if value.value()
+    )
+}
+
+impl Handler for EmptyRouteHandler {
+    fn node_kinds(&self) -> NodeKindMask {
+        NodeKindMask::of(&[NodeKind::ObjectLit]).union(NON_ROUTE_KINDS)
+    }
+
+    fn on_enter_node(&mut self, node: ast_view::Node, _ctx: &mut Context) -> Control {
+        skip_non_route(node)
+    }
+
+    fn object_lit(&mut self, object_lit: &ast_view::ObjectLit, ctx: &mut Context) -> Control {
+        if !is_route_object(object_lit) {
+            return Control::Continue;
+        }
+        // 有展开运算符时，属性可能来自别的对象
+        let has_spread = object_lit
+            .props
+            .iter()
+            .any(|prop| matches!(prop, ast_view::PropOrSpread::Spread(_)));
+        if has_spread {
+            return Control::Continue;
+        }
+
+        if !self
+            .framework
+            .render_keys()
+            .iter()
+            .any(|key| has_prop(object_lit, key))
+        {
+            let message = format!(
+                "🚨 路由没有配置 {} 中的任何一个，访问时只会渲染出空白页面！",
+                self.framework.render_keys().join("、")
+            );
+            ctx.add_diagnostic(object_lit.span(), CODE, message);
+            return Control::Continue;
+        }
+
+        let child_routes = match self
+            .framework
+            .children_keys()
+            .iter()
+            .find_map(|key| prop_value(object_lit, key))
+        {
+            Some(ast_view::Expr::Array(array_lit)) => array_lit,
+            _ => return Control::Continue,
+        };
+        if child_routes.elems.is_empty() {
+            ctx.add_diagnostic(child_routes.span(), CODE, EMPTY_ROUTES_MESSAGE);
+            return Control::Continue;
+        }
+
+        let children = route_objects(child_routes);
+        let has_component = self
+            .framework
+            .component_keys()
+            .iter()
+            .any(|key| has_prop(object_lit, key));
+        if !has_component
+            && children.len() == child_routes.elems.len()
+            && children.iter().all(|child| is_hidden_in_menu(child))
+        {
+            ctx.add_diagnostic(object_lit.span(), CODE, ALL_HIDDEN_MESSAGE);
+        }
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ALL_HIDDEN_MESSAGE, CODE, EMPTY_ROUTES_MESSAGE};
+    use crate::rules::test_util::{config, lint, lint_with, texts};
+    use serde_json::json;
+
+    #[test]
+    fn allows_routes_that_render_something() {
+        let source = "export default [
+            { path: '/a', component: './A' },
+            { path: '/b', redirect: '/a' },
+            { path: '/c', routes: [{ path: 'd', component: './D' }] },
+            { path: '/e', component: './E', routes: [{ path: 'f', hideInMenu: true, component: './F' }] },
+        ];";
+        assert!(lint(CODE, source).is_empty());
+    }
+
+    #[test]
+    fn reports_routes_without_render_keys() {
+        let source = "export default [{ path: '/a', name: 'a' }, { path: '/b', element: <B /> }];";
+        let diagnostics = lint(CODE, source);
+        // umi 的路由不支持 element
+        assert_eq!(
+            texts(source, &diagnostics),
+            [
+                "{ path: '/a', name: 'a' }",
+                "{ path: '/b', element: <B /> }"
+            ]
+        );
+        assert!(diagnostics[0].message.contains("component、routes"));
+    }
+
+    #[test]
+    fn reports_empty_children() {
+        let source = "export default [{ path: '/a', routes: [] }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(texts(source, &diagnostics), ["[]"]);
+        assert_eq!(diagnostics[0].message, EMPTY_ROUTES_MESSAGE);
+    }
+
+    #[test]
+    fn reports_children_all_hidden_in_menu() {
+        let source = "export default [{ path: '/a', routes: [{ path: 'b', hideInMenu: true, component: './B' }] }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, ALL_HIDDEN_MESSAGE);
+
+        // 有一个子路由不是静态的对象时无法确定
+        let source = "export default [{ path: '/a', routes: [{ path: 'b', hideInMenu: true, component: './B' }, other] }];";
+        assert!(lint(CODE, source).is_empty());
+    }
+
+    #[test]
+    fn uses_framework_keys() {
+        let react_router = config(json!({ "framework": "react-router" }));
+        let source = "export default [{ path: '/', element: <Layout />, children: [{ index: true, element: <Home /> }] }];";
+        assert!(lint_with(react_router, CODE, source).is_empty());
+
+        let vue_router = config(json!({ "framework": "vue-router" }));
+        let source = "export default [{ path: '/', components: { default: Home }, children: [{ path: 'a', component: A, meta: { hideInMenu: true } }] }];";
+        assert!(lint_with(vue_router, CODE, source).is_empty());
+
+        let vue_router = config(json!({ "framework": "vue-router" }));
+        let source = "export default [{ path: '/', children: [{ path: 'a', component: A, meta: { hideInMenu: true } }] }];";
+        let diagnostics = lint_with(vue_router, CODE, source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, ALL_HIDDEN_MESSAGE);
+    }
+
+    #[test]
+    fn skips_routes_with_spread() {
+        let source = "export default [{ ...base, path: '/a' }];";
+        assert!(lint(CODE, source).is_empty());
+    }
+}
@@ -0,0 +1,126 @@
+use crate::route::{prop_value, Route, RouteTree};
+
+use super::{Context, LintRule, Program};
+use deno_ast::swc::common::Spanned;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct NestedPath;
+
+const CODE: &str = "nested-path";
+
+impl LintRule for NestedPath {
+    fn code(&self) -> &'static str {
+        CODE
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
+    }
+
+    fn lint_program_with_ast_view(&self, context: &mut Context, program: Program<'_>) {
+        for tree in RouteTree::from_program(program, context.config().framework) {
+            for route in &tree.routes {
+                check_children(route, context);
+            }
+        }
+    }
+}
+
+impl NestedPath {
+    pub fn new() -> Arc<Self> {
+        Arc::new(NestedPath)
+    }
+}
+
+/**
+ * 子路由的绝对路径必须以父路由的路径开头，否则菜单高亮和面包屑都会出错
+ */
+fn check_children(parent: &Route, ctx: &mut Context) {
+    let parent_path = parent.full_path.trim_end_matches('/');
+    for child in &parent.routes {
+        check_child(parent_path, child, ctx);
+        check_children(child, ctx);
+    }
+}
+
+/**
+ * 改成相对路径会改变访问的 URL，所以只给出建议，不自动修复
+ */
+fn check_child(parent_path: &str, child: &Route, ctx: &mut Context) {
+    if parent_path.is_empty() {
+        return;
+    }
+    let path = match &child.path {
+        Some(path) if path.starts_with('/') => path,
+        _ => return,
+    };
+    if path == parent_path || path.starts_with(&format!("{}/", parent_path)) {
+        return;
+    }
+
+    let span = match prop_value(child.object, "path") {
+        Some(value) => value.span(),
+        None => return,
+    };
+    let relative = path.trim_start_matches('/');
+    ctx.add_diagnostic_with_hint(
+        span,
+        CODE,
+        format!(
+            "🚨 子路由的 path `{}` 不在父路由 `{}` 下面，菜单高亮和面包屑都会出错！",
+            path, parent_path
+        ),
+        format!(
+            "如果要保留 `{}` 这个 URL，请把路由移到对应的父路由下面；否则可以改为相对路径 `{}`，也就是 `{}/{}`",
+            path, relative, parent_path, relative
+        ),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CODE;
+    use crate::rules::test_util::{lint, texts};
+
+    #[test]
+    fn allows_nested_and_relative_paths() {
+        let source = "export default [{ path: '/admin', routes: [
+            { path: '/admin', component: './A' },
+            { path: '/admin/users', component: './U' },
+            { path: 'reports', component: './R' },
+        ] }, { path: '/', routes: [{ path: '/login', component: './L' }] }];";
+        assert!(lint(CODE, source).is_empty());
+    }
+
+    #[test]
+    fn reports_paths_outside_parent_without_fix() {
+        let source = "export default [{ path: '/admin', routes: [
+            { path: '/administrator', component: './A' },
+            { path: '/reports/x', component: './X' },
+        ] }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(
+            texts(source, &diagnostics),
+            ["'/administrator'", "'/reports/x'"]
+        );
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.fixes.is_empty()));
+        assert!(diagnostics[1]
+            .hint
+            .as_deref()
+            .unwrap()
+            .contains("`reports/x`，也就是 `/admin/reports/x`"));
+    }
+
+    #[test]
+    fn reports_in_source_order() {
+        let source = "export default [{ path: '/a', routes: [
+            { path: '/b', routes: [{ path: '/c', component: './C' }] },
+            { path: '/d', component: './D' },
+        ] }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(texts(source, &diagnostics), ["'/b'", "'/c'", "'/d'"]);
+    }
+}
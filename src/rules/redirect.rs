@@ -1,10 +1,12 @@
-﻿use crate::handler::{Handler, Traverse};
+use crate::diagnostic::LintFix;
+use crate::fix::remove_props;
+use crate::handler::{Control, Handler, NodeKindMask};
+use crate::route::{prop_key, skip_non_route, NON_ROUTE_KINDS};
 
-use super::{Context, LintRule, Program, ProgramRef};
-use deno_ast::{
-    swc::common::Spanned,
-    view::{self as ast_view, NodeTrait},
-};
+use super::{Context, LintRule};
+use crate::config::Framework;
+use deno_ast::view::NodeKind;
+use deno_ast::{swc::common::Spanned, view as ast_view};
 use std::sync::Arc;
 
 const MESSAGE: &str = "🚨 redirect 路由中应该只配置 redirect 和 path 两个属性！";
@@ -19,15 +21,22 @@ impl LintRule for RedirectKeys {
         CODE
     }
 
-    fn lint_program<'view>(&self, _context: &mut Context<'view>, _program: ProgramRef<'view>) {
-        unreachable!();
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
     }
 
-    fn lint_program_with_ast_view(&self, context: &mut Context, program: Program<'_>) {
-        RedirectKeysHandler.traverse(program, context);
+    fn frameworks(&self) -> &'static [Framework] {
+        // react-router 和 vue-router 中的写法不一样
+        &[Framework::Umi]
     }
 
-    fn new() -> Arc<Self> {
+    fn handler(&self, _context: &Context) -> Option<Box<dyn Handler>> {
+        Some(Box::new(RedirectKeysHandler))
+    }
+}
+
+impl RedirectKeys {
+    pub fn new() -> Arc<Self> {
         Arc::new(RedirectKeys)
     }
 }
@@ -35,17 +44,95 @@ impl LintRule for RedirectKeys {
 struct RedirectKeysHandler;
 
 impl Handler for RedirectKeysHandler {
-    fn object_lit(&mut self, object_lit: &ast_view::ObjectLit, ctx: &mut Context) {
+    fn node_kinds(&self) -> NodeKindMask {
+        NodeKindMask::of(&[NodeKind::ObjectLit]).union(NON_ROUTE_KINDS)
+    }
+
+    fn on_enter_node(&mut self, node: ast_view::Node, _ctx: &mut Context) -> Control {
+        skip_non_route(node)
+    }
+
+    fn object_lit(&mut self, object_lit: &ast_view::ObjectLit, ctx: &mut Context) -> Control {
         let obj_keys: Vec<String> = object_lit
-            .children()
+            .props
             .iter()
-            .map(|obj_name| obj_name.children()[0].text().to_string())
+            .filter_map(prop_key)
+            .map(|(key, _)| key)
             .collect();
-        if obj_keys.len() > 2
+        if object_lit.props.len() > 2
             && obj_keys.contains(&String::from("path"))
             && obj_keys.contains(&String::from("redirect"))
         {
-            ctx.add_diagnostic(object_lit.span(), CODE, MESSAGE);
+            // 展开和计算属性中可能有 path 或者 redirect，这时只报告，不自动修复
+            if object_lit.props.iter().any(|prop| prop_key(prop).is_none()) {
+                ctx.add_diagnostic(object_lit.span(), CODE, MESSAGE);
+                return Control::Continue;
+            }
+            let changes = remove_props(
+                object_lit,
+                |prop| matches!(prop_key(prop), Some((key, _)) if key != "path" && key != "redirect"),
+                ctx,
+            );
+            let fix = LintFix {
+                description: "删除 redirect 和 path 之外的属性".to_string(),
+                changes,
+            };
+            ctx.add_diagnostic_with_fixes(object_lit.span(), CODE, MESSAGE, None, vec![fix]);
         }
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CODE, MESSAGE};
+    use crate::rules::test_util::{fix, lint, texts};
+
+    #[test]
+    fn allows_redirect_with_path() {
+        let source = "export default [{ path: '/', redirect: '/home' }];";
+        assert!(lint(CODE, source).is_empty());
+    }
+
+    #[test]
+    fn reports_extra_keys() {
+        let source = "export default [{ path: '/', redirect: '/home', component: './Home' }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, MESSAGE);
+        assert_eq!(
+            texts(source, &diagnostics),
+            ["{ path: '/', redirect: '/home', component: './Home' }"]
+        );
+    }
+
+    #[test]
+    fn removes_extra_keys() {
+        let source = "export default [{ component: './Home', path: '/', redirect: '/home' }];";
+        assert_eq!(
+            fix(CODE, source),
+            "export default [{ path: '/', redirect: '/home' }];"
+        );
+
+        let source = "export default [\n  {\n    path: '/',\n    redirect: '/home', // 跳转到首页\n    // 首页组件\n    component: './Home',\n  },\n];";
+        assert_eq!(
+            fix(CODE, source),
+            "export default [\n  {\n    path: '/',\n    redirect: '/home', // 跳转到首页\n  },\n];"
+        );
+    }
+
+    #[test]
+    fn does_not_fix_spread_or_computed_keys() {
+        let source =
+            "const base = {};\nexport default [{ path: '/', redirect: '/home', ...base }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].fixes.is_empty());
+
+        let source =
+            "const key = 'name';\nexport default [{ path: '/', redirect: '/home', [key]: 'a' }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0].fixes.is_empty());
     }
 }
@@ -1,8 +1,12 @@
-﻿use crate::handler::{Handler, Traverse};
+use crate::handler::{Control, Handler, NodeKindMask};
+use crate::route::{
+    prop_value, resolve_path, route_objects, skip_non_route, static_string, NON_ROUTE_KINDS,
+};
 
-use super::{Context, LintRule, Program, ProgramRef};
+use super::{Context, LintRule};
 use deno_ast::swc::common::Spanned;
-use deno_ast::view::{self as ast_view, NodeTrait};
+use deno_ast::view as ast_view;
+use deno_ast::view::NodeKind;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -11,122 +15,94 @@ const MESSAGE: &str = "🚨 path发现重复，可能会导致路径渲染错误
 #[derive(Debug)]
 pub struct RepeatPath;
 
-const CODE: &str = "redirect-only-has-redirect-and-path";
+const CODE: &str = "no-repeat-path";
 
 impl LintRule for RepeatPath {
     fn code(&self) -> &'static str {
         CODE
     }
 
-    fn lint_program<'view>(&self, _context: &mut Context<'view>, _program: ProgramRef<'view>) {
-        unreachable!();
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
     }
 
-    fn lint_program_with_ast_view(&self, context: &mut Context, program: Program<'_>) {
-        RedirectKeysHandler.traverse(program, context);
+    fn handler(&self, _context: &Context) -> Option<Box<dyn Handler>> {
+        Some(Box::new(RedirectKeysHandler))
     }
+}
 
-    fn new() -> Arc<Self> {
+impl RepeatPath {
+    pub fn new() -> Arc<Self> {
         Arc::new(RepeatPath)
     }
 }
 
 struct RedirectKeysHandler;
 
-fn find_all_router_path<'a>(node_list: deno_ast::view::Node, mut parent_path: String) -> String {
-    use deno_ast::view::Node::*;
-    if node_list.kind() == deno_ast::view::NodeKind::ExportDefaultExpr
-        || node_list.kind() == deno_ast::view::NodeKind::Module
-    {
-        return parent_path;
-    }
-    for node in node_list.parent() {
-        match node {
-            ObjectLit(n) => {
-                parent_path = find_all_router_path(n.as_node(), parent_path);
-                for child in n.children() {
-                    if child.children()[0].text() == "path" {
-                        let children_path = child.children()[1].text().to_string().replace("'", "");
-                        if !children_path.starts_with("/") && !children_path.starts_with(".") {
-                            parent_path.push_str(&children_path.as_str());
-                        } else if children_path.starts_with(".") {
-                            parent_path.push_str(children_path.as_str().replace("./", "").as_str());
-                        } else {
-                            parent_path = children_path;
-                        }
-                    }
-                }
-            }
-            n => {
-                parent_path = find_all_router_path(n, parent_path);
-            }
-        }
-    }
-
-    parent_path
-}
-
 /**
- * 遍历routes 的结构
+ * 遍历routes 的结构，父路由的路径从遍历时所在的路由中获取
  */
-fn loops_router_array(array_node: &ast_view::ArrayLit, mut context: Vec<String>) -> Vec<String> {
-    for item in array_node.children() {
-        if item.kind() == deno_ast::view::NodeKind::ExprOrSpread {
-            let obj = item.children()[0];
-            for obj_name in obj.children() {
-                // * 寻找 children 找到子节点
-                if obj_name.kind() == deno_ast::view::NodeKind::KeyValueProp {
-                    let key = obj_name.children()[0];
-                    let value = obj_name.children()[1];
-
-                    if key.kind() == deno_ast::view::NodeKind::Ident {
-                        if key.text() == "path" {
-                            let children_path = value.text().replace("'", "");
-                            let mut parent_path = "/".to_string();
-                            // 如果是用 / 开头的不用拼接 ，别的都需要
-                            if !children_path.starts_with("/") {
-                                parent_path =
-                                    find_all_router_path(array_node.parent(), "/".to_string());
-                            }
-
-                            if children_path != "/" {
-                                if !children_path.starts_with("/")
-                                    && !children_path.starts_with(".")
-                                {
-                                    parent_path.push_str(children_path.as_str());
-                                } else if children_path.starts_with(".") {
-                                    parent_path.push_str(
-                                        children_path.as_str().replace("./", "/").as_str(),
-                                    );
-                                } else {
-                                    parent_path = children_path;
-                                }
-
-                                if !parent_path.eq("/") && parent_path.len() > 1 {
-                                    context.push(parent_path);
-                                }
-                            }
-                        }
-                    }
-                }
+fn loops_router_array(array_node: &ast_view::ArrayLit, parent_path: &str) -> Vec<String> {
+    let mut context = vec![];
+    for object in route_objects(array_node) {
+        if let Some(children_path) =
+            prop_value(object, "path").and_then(|path| static_string(&path))
+        {
+            // 如果是用 / 开头的不用拼接 ，别的都需要
+            let path = resolve_path(parent_path, &children_path);
+            if children_path != "/" && path != "/" {
+                context.push(path);
             }
         }
     }
-    return context;
+    context
 }
 
 impl Handler for RedirectKeysHandler {
-    fn array_lit(&mut self, array_lit: &ast_view::ArrayLit, ctx: &mut Context) {
-        // 遍历dom，获取所有的 path
-        let path_array: Vec<String> = loops_router_array(array_lit, vec![]);
+    fn node_kinds(&self) -> NodeKindMask {
+        NodeKindMask::of(&[NodeKind::ArrayLit]).union(NON_ROUTE_KINDS)
+    }
+
+    fn on_enter_node(&mut self, node: ast_view::Node, _ctx: &mut Context) -> Control {
+        skip_non_route(node)
+    }
+
+    fn array_lit(&mut self, array_lit: &ast_view::ArrayLit, ctx: &mut Context) -> Control {
+        // 数组所在的路由就是这些路由的父路由
+        let parent_path = ctx
+            .current_route()
+            .map_or("/", |route| route.full_path.as_str());
+        let path_array: Vec<String> = loops_router_array(array_lit, parent_path);
         let mut path_map: HashMap<String, bool> = HashMap::new();
         // 判断是否有重复的path
         for path in path_array {
             if path_map.contains_key(&path) {
                 ctx.add_diagnostic(array_lit.span(), CODE, MESSAGE);
-            } else if !path.eq("'/'") {
-                path_map.insert(path, true);
+            } else {
+                path_map.entry(path).or_insert(true);
             }
         }
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::rules::get_filtered_rules;
+    use crate::rules::test_util::config;
+    use serde_json::json;
+
+    #[test]
+    fn legacy_code_still_disables_rule() {
+        let config = config(json!({
+            "rules": { "exclude": ["redirect-only-has-redirect-and-path"] }
+        }));
+        let codes: Vec<_> = get_filtered_rules(&config)
+            .iter()
+            .map(|rule| rule.code())
+            .collect();
+        assert!(!codes.contains(&"no-repeat-path"));
+        assert!(!codes.contains(&"redirect-only-has-redirect-and-path"));
+        assert!(codes.contains(&"no-repeat-name"));
     }
 }
@@ -0,0 +1,140 @@
+use crate::config::UniqueNameScope;
+use crate::route::{prop_value, static_string, Route, RouteTree};
+
+use super::{Context, LintRule, Program};
+use deno_ast::swc::common::{Span, Spanned};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct RepeatName;
+
+const CODE: &str = "no-repeat-name";
+
+impl LintRule for RepeatName {
+    fn code(&self) -> &'static str {
+        CODE
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
+    }
+
+    fn lint_program_with_ast_view(&self, context: &mut Context, program: Program<'_>) {
+        let scope = context.config().unique_name_scope;
+        for tree in RouteTree::from_program(program, context.config().framework) {
+            match scope {
+                UniqueNameScope::Siblings => check_siblings(&tree.routes, context),
+                UniqueNameScope::Tree => {
+                    let mut routes = vec![];
+                    tree.walk(&mut |route| routes.push(route_name(route)));
+                    report_duplicates(routes.into_iter().flatten(), scope, context);
+                }
+            }
+        }
+    }
+}
+
+impl RepeatName {
+    pub fn new() -> Arc<Self> {
+        Arc::new(RepeatName)
+    }
+}
+
+/**
+ * 获取路由的 name 和它的值所在的位置
+ */
+fn route_name(route: &Route) -> Option<(String, Span)> {
+    let value = prop_value(route.object, "name")?;
+    static_string(&value).map(|name| (name, value.span()))
+}
+
+fn check_siblings(routes: &[Route], ctx: &mut Context) {
+    report_duplicates(
+        routes.iter().filter_map(route_name),
+        UniqueNameScope::Siblings,
+        ctx,
+    );
+    for route in routes {
+        check_siblings(&route.routes, ctx);
+    }
+}
+
+/**
+ * 第一次出现的 name 不报错，之后每一次重复都单独报告
+ */
+fn report_duplicates(
+    names: impl Iterator<Item = (String, Span)>,
+    scope: UniqueNameScope,
+    ctx: &mut Context,
+) {
+    let mut first_seen: HashMap<String, Span> = HashMap::new();
+    for (name, span) in names {
+        let first = match first_seen.get(&name) {
+            Some(first) => *first,
+            None => {
+                first_seen.insert(name, span);
+                continue;
+            }
+        };
+        let first_line = ctx.range(first).start.line_index + 1;
+        let message = match scope {
+            UniqueNameScope::Siblings => format!(
+                "🚨 同一层路由中 name `{}` 重复了，菜单的 key 会冲突！",
+                name
+            ),
+            UniqueNameScope::Tree => {
+                format!("🚨 路由 name `{}` 重复了，国际化的 key 会冲突！", name)
+            }
+        };
+        ctx.add_diagnostic_with_hint(
+            span,
+            CODE,
+            message,
+            format!("第一次出现在第 {} 行", first_line),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CODE;
+    use crate::rules::test_util::{config, lint, lint_with, texts};
+    use serde_json::json;
+
+    const SOURCE: &str = "export default [
+  { path: '/a', name: 'list', routes: [{ path: 'x', name: 'list' }] },
+  { path: '/b', name: 'detail', routes: [{ path: 'y', name: 'list' }] },
+];";
+
+    #[test]
+    fn allows_unique_names() {
+        let source = "export default [{ path: '/a', name: 'a' }, { path: '/b', name: 'b' }, { path: '/c', name: title }];";
+        assert!(lint(CODE, source).is_empty());
+        // 同名但是不在同一层
+        assert!(lint(CODE, SOURCE).is_empty());
+    }
+
+    #[test]
+    fn reports_every_duplicate_between_siblings() {
+        let source = "export default [
+  { path: '/a', name: 'a' },
+  { path: '/b', name: 'a' },
+  { path: '/c', name: `a` },
+];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(texts(source, &diagnostics), ["'a'", "`a`"]);
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.hint.as_deref() == Some("第一次出现在第 2 行")));
+    }
+
+    #[test]
+    fn checks_whole_tree_when_configured() {
+        let config = config(json!({ "uniqueNameScope": "tree" }));
+        let diagnostics = lint_with(config, CODE, SOURCE);
+        assert_eq!(texts(SOURCE, &diagnostics), ["'list'", "'list'"]);
+        assert!(diagnostics[0].message.contains("国际化"));
+        assert_eq!(diagnostics[1].hint.as_deref(), Some("第一次出现在第 2 行"));
+    }
+}
@@ -0,0 +1,231 @@
+use crate::diagnostic::{LintFix, LintFixChange};
+use crate::handler::{Control, Handler, NodeKindMask};
+use crate::route::{
+    has_prop, is_routes_array, parent_route, prop_value, route_objects, skip_non_route,
+    static_string, NON_ROUTE_KINDS,
+};
+
+use super::{Context, LintRule};
+use crate::config::Framework;
+use deno_ast::swc::common::{BytePos, Span, Spanned};
+use deno_ast::view as ast_view;
+use deno_ast::view::NodeKind;
+use deno_ast::MediaType;
+use std::sync::Arc;
+
+const MISSING_MESSAGE: &str = "🚨 这一层路由缺少 404 兜底路由，访问不存在的路径会渲染出空白页面！";
+const NOT_LAST_MESSAGE: &str = "🚨 404 兜底路由应该放在最后，否则后面的路由永远不会被匹配到！";
+
+#[derive(Debug)]
+pub struct Require404;
+
+const CODE: &str = "require-404";
+
+impl LintRule for Require404 {
+    fn code(&self) -> &'static str {
+        CODE
+    }
+
+    fn frameworks(&self) -> &'static [Framework] {
+        // react-router 和 vue-router 中的写法不一样
+        &[Framework::Umi]
+    }
+
+    fn handler(&self, _context: &Context) -> Option<Box<dyn Handler>> {
+        Some(Box::new(Require404Handler))
+    }
+}
+
+impl Require404 {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Require404)
+    }
+}
+
+struct Require404Handler;
+
+/**
+ * 没有 path（或者 path 是 `*`），也没有子路由和重定向的路由就是兜底路由
+ */
+fn is_catch_all(route: &ast_view::ObjectLit) -> bool {
+    let catch_all_path = match prop_value(route, "path") {
+        None => true,
+        Some(path) => matches!(static_string(&path).as_deref(), Some("*") | Some("/*")),
+    };
+    catch_all_path
+        && !has_prop(route, "routes")
+        && !has_prop(route, "children")
+        && !has_prop(route, "redirect")
+}
+
+/// JSON 文件中 key 和字符串都必须使用双引号
+fn not_found_route(component: &str, ctx: &Context) -> String {
+    if ctx.media_type() == MediaType::Json {
+        format!("{{ \"component\": \"{}\" }}", component)
+    } else {
+        format!("{{ component: '{}' }}", component)
+    }
+}
+
+fn insert_fix(pos: BytePos, route: String, new_text: String, ctx: &Context) -> LintFix {
+    LintFix {
+        description: format!("在最后追加 {}", route),
+        changes: vec![LintFixChange {
+            new_text,
+            range: ctx.range(Span::new(pos, pos, Default::default())),
+        }],
+    }
+}
+
+/**
+ * 在最后一个路由后面追加 404 路由，尽量保持原来的缩进
+ */
+fn append_fix(last: Span, component: &str, ctx: &Context) -> LintFix {
+    let text = ctx.source_file().text();
+    let lo = last.lo.0 as usize;
+    let line_start = text[..lo].rfind('\n').map(|index| index + 1).unwrap_or(0);
+    let indent = &text[line_start..lo];
+    let route = not_found_route(component, ctx);
+    let new_text = if indent.chars().all(char::is_whitespace) {
+        format!(",\n{}{}", indent, route)
+    } else {
+        format!(", {}", route)
+    };
+    insert_fix(last.hi, route, new_text, ctx)
+}
+
+/// 空数组直接在 `[` 后面插入 404 路由
+fn fill_fix(array_lit: &ast_view::ArrayLit, component: &str, ctx: &Context) -> LintFix {
+    let route = not_found_route(component, ctx);
+    let pos = array_lit.span().lo + BytePos(1);
+    insert_fix(pos, route.clone(), route, ctx)
+}
+
+impl Handler for Require404Handler {
+    fn node_kinds(&self) -> NodeKindMask {
+        NodeKindMask::of(&[NodeKind::ArrayLit]).union(NON_ROUTE_KINDS)
+    }
+
+    fn on_enter_node(&mut self, node: ast_view::Node, _ctx: &mut Context) -> Control {
+        skip_non_route(node)
+    }
+
+    fn array_lit(&mut self, array_lit: &ast_view::ArrayLit, ctx: &mut Context) -> Control {
+        if !is_routes_array(array_lit) {
+            return Control::Continue;
+        }
+        // 只检查顶层和有布局组件的那一层，没有 component 的父路由只是用来分组
+        if let Some(parent) = parent_route(array_lit) {
+            if !has_prop(parent, "component") {
+                return Control::Continue;
+            }
+        }
+        let component = ctx.config().not_found_component().to_string();
+        // 最后一项是展开运算符之类的写法时无法判断
+        let last = match array_lit.elems.last() {
+            Some(Some(item)) if item.inner.spread.is_none() => item,
+            Some(_) => return Control::Continue,
+            None => {
+                let fix = fill_fix(array_lit, &component, ctx);
+                ctx.add_diagnostic_with_fixes(
+                    array_lit.span(),
+                    CODE,
+                    MISSING_MESSAGE,
+                    None,
+                    vec![fix],
+                );
+                return Control::Continue;
+            }
+        };
+
+        let catch_alls: Vec<_> = route_objects(array_lit)
+            .into_iter()
+            .filter(|route| is_catch_all(route))
+            .collect();
+        if catch_alls.is_empty() {
+            let fix = append_fix(last.span(), &component, ctx);
+            ctx.add_diagnostic_with_fixes(array_lit.span(), CODE, MISSING_MESSAGE, None, vec![fix]);
+        }
+        // 有多个兜底路由时，除了最后一项其余的都会让后面的路由匹配不到
+        for route in catch_alls {
+            if route.span() != last.span() {
+                ctx.add_diagnostic(route.span(), CODE, NOT_LAST_MESSAGE);
+            }
+        }
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CODE, MISSING_MESSAGE, NOT_LAST_MESSAGE};
+    use crate::rules::test_util::{config, fix, fix_with, lint, lint_with, texts};
+    use serde_json::json;
+
+    #[test]
+    fn allows_catch_all_at_the_end() {
+        let source = "export default [
+  { path: '/', component: './L', routes: [{ path: 'a', component: './A' }, { path: '*', component: './404' }] },
+  { component: './404' },
+];";
+        assert!(lint(CODE, source).is_empty());
+        // 没有 component 的父路由只是用来分组
+        let source = "export default [{ path: '/a', routes: [{ path: 'b', component: './B' }] }, { component: './404' }];";
+        assert!(lint(CODE, source).is_empty());
+    }
+
+    #[test]
+    fn reports_missing_catch_all_with_fix() {
+        let source = "export default [
+  { path: '/a', component: './A' },
+];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, MISSING_MESSAGE);
+        assert_eq!(
+            fix(CODE, source),
+            "export default [
+  { path: '/a', component: './A' },
+  { component: './404' },
+];"
+        );
+    }
+
+    #[test]
+    fn reports_empty_routes() {
+        let config = || config(json!({ "notFoundComponent": "@/pages/404" }));
+        let source =
+            "export default [{ path: '/', component: './L', routes: [] }, { component: './404' }];";
+        let diagnostics = lint_with(config(), CODE, source);
+        assert_eq!(texts(source, &diagnostics), ["[]"]);
+        assert_eq!(diagnostics[0].message, MISSING_MESSAGE);
+
+        let source = "export default [];";
+        assert_eq!(
+            fix_with(config(), CODE, source),
+            "export default [{ component: '@/pages/404' }];"
+        );
+    }
+
+    #[test]
+    fn reports_every_catch_all_not_at_the_end() {
+        let source = "export default [
+  { component: './404' },
+  { path: '/a', component: './A' },
+  { path: '*', component: './404' },
+  { path: '/b', component: './B' },
+  { component: './404' },
+];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(
+            texts(source, &diagnostics),
+            [
+                "{ component: './404' }",
+                "{ path: '*', component: './404' }"
+            ]
+        );
+        assert!(diagnostics
+            .iter()
+            .all(|diagnostic| diagnostic.message == NOT_LAST_MESSAGE));
+    }
+}
@@ -0,0 +1,51 @@
+//! 规则测试共用的工具函数
+
+use crate::config::Config;
+use crate::diagnostic::LintDiagnostic;
+use crate::{Linter, MediaType};
+
+/// 把 .routelintrc.json 格式的 JSON 转化为配置
+pub fn config(json: serde_json::Value) -> Config {
+    serde_json::from_value(json).unwrap()
+}
+
+/**
+ * 检查源码，只返回 `code` 这个规则的问题，默认关闭的规则也会开启
+ */
+pub fn lint_with(mut config: Config, code: &str, source: &str) -> Vec<LintDiagnostic> {
+    config.rules.include.push(code.to_string());
+    let result = Linter::new(config)
+        .lint_source("routes.tsx", source.to_string(), MediaType::Tsx)
+        .unwrap();
+    result
+        .diagnostics
+        .into_iter()
+        .filter(|diagnostic| diagnostic.code == code)
+        .collect()
+}
+
+/// 使用默认配置检查
+pub fn lint(code: &str, source: &str) -> Vec<LintDiagnostic> {
+    lint_with(Config::default(), code, source)
+}
+
+/// 问题所在位置的源码
+pub fn texts<'a>(source: &'a str, diagnostics: &[LintDiagnostic]) -> Vec<&'a str> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| &source[diagnostic.range.start.byte_pos..diagnostic.range.end.byte_pos])
+        .collect()
+}
+
+/**
+ * 应用 `code` 这个规则的自动修复，返回修复后的源码
+ */
+pub fn fix_with(config: Config, code: &str, source: &str) -> String {
+    let diagnostics = lint_with(config, code, source);
+    crate::fix::apply_fixes(source, &diagnostics).0
+}
+
+/// 使用默认配置修复
+pub fn fix(code: &str, source: &str) -> String {
+    fix_with(Config::default(), code, source)
+}
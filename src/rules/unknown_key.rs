@@ -0,0 +1,131 @@
+use crate::handler::{Control, Handler, NodeKindMask};
+use crate::route::{is_route_object, prop_key, skip_non_route, NON_ROUTE_KINDS};
+use crate::schema::RouteSchema;
+
+use super::{Context, LintRule};
+use deno_ast::view as ast_view;
+use deno_ast::view::NodeKind;
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct UnknownKey;
+
+const CODE: &str = "no-unknown-key";
+
+impl LintRule for UnknownKey {
+    fn code(&self) -> &'static str {
+        CODE
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
+    }
+
+    fn handler(&self, context: &Context) -> Option<Box<dyn Handler>> {
+        let schema = RouteSchema::new(context.config());
+        Some(Box::new(UnknownKeyHandler { schema }))
+    }
+}
+
+impl UnknownKey {
+    pub fn new() -> Arc<Self> {
+        Arc::new(UnknownKey)
+    }
+}
+
+struct UnknownKeyHandler {
+    schema: RouteSchema,
+}
+
+impl Handler for UnknownKeyHandler {
+    fn node_kinds(&self) -> NodeKindMask {
+        NodeKindMask::of(&[NodeKind::ObjectLit]).union(NON_ROUTE_KINDS)
+    }
+
+    fn on_enter_node(&mut self, node: ast_view::Node, _ctx: &mut Context) -> Control {
+        skip_non_route(node)
+    }
+
+    fn object_lit(&mut self, object_lit: &ast_view::ObjectLit, ctx: &mut Context) -> Control {
+        if !is_route_object(object_lit) {
+            return Control::Continue;
+        }
+
+        for (key, span) in object_lit.props.iter().filter_map(prop_key) {
+            if self.schema.contains(&key) {
+                continue;
+            }
+            let message = format!("🚨 未知的路由属性 `{}`，该属性不会生效！", key);
+            match self.schema.suggest(&key) {
+                Some(suggestion) => ctx.add_diagnostic_with_hint(
+                    span,
+                    CODE,
+                    message,
+                    format!("你是不是想写 `{}`？", suggestion),
+                ),
+                None => ctx.add_diagnostic_with_hint(
+                    span,
+                    CODE,
+                    message,
+                    "如果这是项目自定义的属性，请在 .routelintrc.json 的 schema.extraKeys 中声明",
+                ),
+            }
+        }
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CODE;
+    use crate::rules::test_util::{config, lint, lint_with, texts};
+    use serde_json::json;
+
+    #[test]
+    fn allows_known_keys() {
+        let source =
+            "export default [{ path: '/a', component: './A', name: 'a', hideInMenu: true }];";
+        assert!(lint(CODE, source).is_empty());
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_suggestion() {
+        let source = "export default [{ path: '/a', compnent: './A', keepAlive: true }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(texts(source, &diagnostics), ["compnent", "keepAlive"]);
+        assert_eq!(
+            diagnostics[0].hint.as_deref(),
+            Some("你是不是想写 `component`？")
+        );
+        assert!(diagnostics[1]
+            .hint
+            .as_deref()
+            .unwrap()
+            .contains("extraKeys"));
+    }
+
+    #[test]
+    fn allows_extra_keys_from_config() {
+        let source = "export default [{ path: '/a', keepAlive: true }];";
+        let config = config(json!({ "schema": { "extraKeys": ["keepAlive"] } }));
+        assert!(lint_with(config, CODE, source).is_empty());
+    }
+
+    #[test]
+    fn uses_framework_fields() {
+        let source = "export default [{ path: '/a', element: <A />, routes: [] }];";
+        let config = config(json!({ "framework": "react-router" }));
+        let diagnostics = lint_with(config, CODE, source);
+        assert_eq!(texts(source, &diagnostics), ["routes"]);
+
+        // umi 中没有 element
+        assert_eq!(texts(source, &lint(CODE, source)), ["element"]);
+    }
+
+    #[test]
+    fn ignores_objects_outside_routes() {
+        let source = "export default [{ path: '/a', meta: { foo: 1 }, wrappers: ['@/w'] }];\nconst x = { foo: 1 };";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(texts(source, &diagnostics), ["meta"]);
+    }
+}
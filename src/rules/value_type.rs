@@ -0,0 +1,171 @@
+use crate::handler::{Control, Handler, NodeKindMask};
+use crate::route::{is_route_object, prop_name, skip_non_route, value_kind, NON_ROUTE_KINDS};
+use crate::schema::{RouteSchema, ValueKind};
+
+use super::{Context, LintRule};
+use deno_ast::view::NodeKind;
+use deno_ast::{swc::common::Spanned, view as ast_view};
+use std::sync::Arc;
+
+#[derive(Debug)]
+pub struct ValueType;
+
+const CODE: &str = "route-value-type";
+
+impl LintRule for ValueType {
+    fn code(&self) -> &'static str {
+        CODE
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["recommended"]
+    }
+
+    fn handler(&self, context: &Context) -> Option<Box<dyn Handler>> {
+        let schema = RouteSchema::new(context.config());
+        Some(Box::new(ValueTypeHandler { schema }))
+    }
+}
+
+impl ValueType {
+    pub fn new() -> Arc<Self> {
+        Arc::new(ValueType)
+    }
+}
+
+struct ValueTypeHandler {
+    schema: RouteSchema,
+}
+
+/**
+ * 针对常见的写法给出修改建议
+ */
+fn hint_for(expected: &[ValueKind], actual: ValueKind, value: &ast_view::Expr) -> Option<String> {
+    if let ast_view::Expr::Lit(ast_view::Lit::Str(str)) = value {
+        let text = str.value().to_string();
+        if expected.contains(&ValueKind::Boolean) && (text == "true" || text == "false") {
+            return Some(format!("请去掉引号，直接使用 `{}`", text));
+        }
+    }
+    if actual == ValueKind::Template {
+        return Some("这里需要一个静态的字符串，请不要在模板字符串中使用 ${} 表达式".to_string());
+    }
+    if actual == ValueKind::Element && expected.contains(&ValueKind::Function) {
+        return Some("这里需要组件本身，比如 `Home`，JSX 元素请写在 element 中".to_string());
+    }
+    if actual == ValueKind::Function && expected.contains(&ValueKind::Element) {
+        return Some("这里需要 JSX 元素，比如 `<Home />`，组件本身请写在 Component 中".to_string());
+    }
+    if actual == ValueKind::Array && expected.contains(&ValueKind::StringArray) {
+        return Some("数组中只能包含字符串".to_string());
+    }
+    None
+}
+
+impl Handler for ValueTypeHandler {
+    fn node_kinds(&self) -> NodeKindMask {
+        NodeKindMask::of(&[NodeKind::KeyValueProp]).union(NON_ROUTE_KINDS)
+    }
+
+    fn on_enter_node(&mut self, node: ast_view::Node, _ctx: &mut Context) -> Control {
+        skip_non_route(node)
+    }
+
+    fn key_value_prop(
+        &mut self,
+        key_value_prop: &ast_view::KeyValueProp,
+        ctx: &mut Context,
+    ) -> Control {
+        if !is_route_object(key_value_prop.parent()) {
+            return Control::Continue;
+        }
+        let key = match prop_name(&key_value_prop.key) {
+            Some((key, _)) => key,
+            None => return Control::Continue,
+        };
+        let expected = match self.schema.expected_kinds(&key) {
+            Some(expected) => expected,
+            None => return Control::Continue,
+        };
+        let actual = match value_kind(&key_value_prop.value) {
+            Some(actual) => actual,
+            None => return Control::Continue,
+        };
+        if expected.iter().any(|kind| kind.accepts(actual)) {
+            return Control::Continue;
+        }
+
+        let expected_text = expected
+            .iter()
+            .map(|kind| kind.describe())
+            .collect::<Vec<_>>()
+            .join(" 或 ");
+        let message = format!(
+            "🚨 路由属性 `{}` 的值应该是 {}，但这里是 {}！",
+            key,
+            expected_text,
+            actual.describe()
+        );
+        let span = key_value_prop.value.span();
+        match hint_for(expected, actual, &key_value_prop.value) {
+            Some(hint) => ctx.add_diagnostic_with_hint(span, CODE, message, hint),
+            None => ctx.add_diagnostic(span, CODE, message),
+        }
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CODE;
+    use crate::rules::test_util::{config, lint, lint_with, texts};
+    use serde_json::json;
+
+    #[test]
+    fn allows_expected_types() {
+        let source = "export default [{ path: '/a', hideInMenu: true, wrappers: ['@/w'], component: Foo, name: `a${b}` }];";
+        assert!(lint(CODE, source).is_empty());
+    }
+
+    #[test]
+    fn reports_wrong_types_with_hints() {
+        let source =
+            "export default [{ path: 1, hideInMenu: 'true', wrappers: ['@/w', 1] }, { path: `/a/${b}` }];";
+        let diagnostics = lint(CODE, source);
+        assert_eq!(
+            texts(source, &diagnostics),
+            ["1", "'true'", "['@/w', 1]", "`/a/${b}`"]
+        );
+        assert_eq!(diagnostics[0].hint, None);
+        assert_eq!(
+            diagnostics[1].hint.as_deref(),
+            Some("请去掉引号，直接使用 `true`")
+        );
+        assert_eq!(diagnostics[2].hint.as_deref(), Some("数组中只能包含字符串"));
+        assert!(diagnostics[3].hint.as_deref().unwrap().contains("${}"));
+    }
+
+    #[test]
+    fn checks_react_router_elements() {
+        let config = || config(json!({ "framework": "react-router" }));
+        let source = "export default [{ path: '/', element: () => null, Component: <Home /> }];";
+        let diagnostics = lint_with(config(), CODE, source);
+        assert_eq!(texts(source, &diagnostics), ["() => null", "<Home />"]);
+        assert!(diagnostics[0]
+            .hint
+            .as_deref()
+            .unwrap()
+            .contains("Component"));
+        assert!(diagnostics[1].hint.as_deref().unwrap().contains("element"));
+
+        let source = "export default [{ path: '/', element: <Home />, Component: null }];";
+        assert!(lint_with(config(), CODE, source).is_empty());
+    }
+
+    #[test]
+    fn ignores_extra_keys_and_non_routes() {
+        let config = config(json!({ "schema": { "extraKeys": ["keepAlive"] } }));
+        let source = "export default [{ path: '/a', keepAlive: 'yes' }];\nconst x = { path: 1 };";
+        assert!(lint_with(config, CODE, source).is_empty());
+    }
+}
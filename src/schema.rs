@@ -0,0 +1,256 @@
+use crate::config::{Config, Framework};
+
+/// 路由属性的值允许的类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValueKind {
+    /// 字符串或者不带表达式的模板字符串
+    String,
+    /// 带 `${}` 表达式的模板字符串
+    Template,
+    Boolean,
+    Number,
+    Null,
+    Object,
+    Array,
+    /// 只包含字符串的数组
+    StringArray,
+    Function,
+    /// JSX 元素，只有 .jsx / .tsx 中才会出现
+    Element,
+}
+
+impl ValueKind {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            ValueKind::String => "string",
+            ValueKind::Template => "template string",
+            ValueKind::Boolean => "boolean",
+            ValueKind::Number => "number",
+            ValueKind::Null => "null",
+            ValueKind::Object => "object",
+            ValueKind::Array => "array",
+            ValueKind::StringArray => "string[]",
+            ValueKind::Function => "function",
+            ValueKind::Element => "JSX element",
+        }
+    }
+
+    /// 实际的值是否满足期望的类型
+    pub fn accepts(&self, actual: ValueKind) -> bool {
+        *self == actual || (*self == ValueKind::Array && actual == ValueKind::StringArray)
+    }
+}
+
+#[derive(Debug)]
+pub struct RouteField {
+    pub name: &'static str,
+    /// 允许的值类型，为空表示不限制
+    pub kinds: &'static [ValueKind],
+}
+
+const fn field(name: &'static str, kinds: &'static [ValueKind]) -> RouteField {
+    RouteField { name, kinds }
+}
+
+const STRING: &[ValueKind] = &[ValueKind::String, ValueKind::Template];
+const BOOLEAN: &[ValueKind] = &[ValueKind::Boolean];
+const ANY: &[ValueKind] = &[];
+/// react-router 中渲染的元素，比如 `<Home />`
+const ELEMENT: &[ValueKind] = &[ValueKind::Element, ValueKind::Null];
+/// react-router 中的组件本身，比如 `Home`
+const COMPONENT: &[ValueKind] = &[ValueKind::Function, ValueKind::Null];
+
+/// Ant Design Pro / umi 路由对象支持的属性
+pub const UMI_ROUTE_FIELDS: &[RouteField] = &[
+    field("path", &[ValueKind::String]),
+    field("component", ANY),
+    field("routes", &[ValueKind::Array]),
+    field("redirect", STRING),
+    field("exact", BOOLEAN),
+    field("name", STRING),
+    field("icon", ANY),
+    field("title", STRING),
+    field("access", STRING),
+    field("authority", &[ValueKind::String, ValueKind::StringArray]),
+    field("layout", &[ValueKind::Boolean, ValueKind::Object]),
+    field("wrappers", &[ValueKind::StringArray]),
+    field("locale", &[ValueKind::String, ValueKind::Boolean]),
+    field("target", STRING),
+    field("key", STRING),
+    field("parentKeys", &[ValueKind::StringArray]),
+    field("hideInMenu", BOOLEAN),
+    field("hideChildrenInMenu", BOOLEAN),
+    field("hideInBreadcrumb", BOOLEAN),
+    field("flatMenu", BOOLEAN),
+    field("menu", &[ValueKind::Boolean, ValueKind::Object]),
+    field("headerRender", &[ValueKind::Boolean, ValueKind::Function]),
+    field("footerRender", &[ValueKind::Boolean, ValueKind::Function]),
+    field("menuRender", &[ValueKind::Boolean, ValueKind::Function]),
+    field(
+        "menuHeaderRender",
+        &[ValueKind::Boolean, ValueKind::Function],
+    ),
+    field("fixedHeader", BOOLEAN),
+    field("fixSiderbar", BOOLEAN),
+    field("navTheme", STRING),
+    field("headerTheme", STRING),
+    field("microApp", STRING),
+    field("microAppProps", &[ValueKind::Object]),
+    // children 已经废弃，由 no-use-children 单独提示
+    field("children", &[ValueKind::Array]),
+];
+
+/// react-router v6 RouteObject 支持的属性
+pub const REACT_ROUTER_FIELDS: &[RouteField] = &[
+    field("path", &[ValueKind::String]),
+    field("index", BOOLEAN),
+    field("element", ELEMENT),
+    field("Component", COMPONENT),
+    field("lazy", &[ValueKind::Function]),
+    field("children", &[ValueKind::Array]),
+    field("caseSensitive", BOOLEAN),
+    field("id", STRING),
+    field("loader", &[ValueKind::Function]),
+    field("action", &[ValueKind::Function]),
+    field("errorElement", ELEMENT),
+    field("ErrorBoundary", COMPONENT),
+    field("hydrateFallbackElement", ELEMENT),
+    field("HydrateFallback", COMPONENT),
+    field("handle", ANY),
+    field("shouldRevalidate", &[ValueKind::Function]),
+];
+
+/// vue-router RouteRecordRaw 支持的属性
+pub const VUE_ROUTER_FIELDS: &[RouteField] = &[
+    field("path", &[ValueKind::String]),
+    field("name", &[ValueKind::String, ValueKind::Template]),
+    field("component", ANY),
+    field("components", &[ValueKind::Object]),
+    field(
+        "redirect",
+        &[
+            ValueKind::String,
+            ValueKind::Template,
+            ValueKind::Object,
+            ValueKind::Function,
+        ],
+    ),
+    field("alias", &[ValueKind::String, ValueKind::StringArray]),
+    field("children", &[ValueKind::Array]),
+    field("meta", &[ValueKind::Object]),
+    field(
+        "props",
+        &[ValueKind::Boolean, ValueKind::Object, ValueKind::Function],
+    ),
+    field("beforeEnter", &[ValueKind::Function, ValueKind::Array]),
+    field("sensitive", BOOLEAN),
+    field("strict", BOOLEAN),
+    field("end", BOOLEAN),
+];
+
+impl Framework {
+    pub fn route_fields(&self) -> &'static [RouteField] {
+        match self {
+            Framework::Umi => UMI_ROUTE_FIELDS,
+            Framework::ReactRouter => REACT_ROUTER_FIELDS,
+            Framework::VueRouter => VUE_ROUTER_FIELDS,
+        }
+    }
+
+    /// 配置子路由的 key，umi 中的 children 已经废弃但是仍然能用
+    pub fn children_keys(&self) -> &'static [&'static str] {
+        match self {
+            Framework::Umi => &["routes", "children"],
+            Framework::ReactRouter | Framework::VueRouter => &["children"],
+        }
+    }
+
+    /// 配置页面组件的 key
+    pub fn component_keys(&self) -> &'static [&'static str] {
+        match self {
+            Framework::Umi => &["component"],
+            Framework::ReactRouter => &["element", "Component", "lazy"],
+            Framework::VueRouter => &["component", "components"],
+        }
+    }
+
+    /// 只要有其中一个，路由就会渲染一些内容
+    pub fn render_keys(&self) -> &'static [&'static str] {
+        match self {
+            Framework::Umi => &["component", "routes", "children", "redirect"],
+            Framework::ReactRouter => &["element", "Component", "lazy", "children"],
+            Framework::VueRouter => &["component", "components", "children", "redirect"],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RouteSchema {
+    fields: &'static [RouteField],
+    keys: Vec<String>,
+}
+
+impl RouteSchema {
+    pub fn new(config: &Config) -> Self {
+        let fields = config.framework.route_fields();
+        let keys = fields
+            .iter()
+            .map(|field| field.name.to_string())
+            .chain(config.schema.extra_keys.iter().cloned())
+            .collect();
+        RouteSchema { fields, keys }
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.keys.iter().any(|known| known == key)
+    }
+
+    /**
+     * 内置属性允许的值类型，自定义属性和不限制类型的属性返回 None
+     */
+    pub fn expected_kinds(&self, key: &str) -> Option<&'static [ValueKind]> {
+        self.fields
+            .iter()
+            .find(|field| field.name == key && !field.kinds.is_empty())
+            .map(|field| field.kinds)
+    }
+
+    /**
+     * 找到和 key 最接近的合法属性，用于拼写错误的提示
+     */
+    pub fn suggest(&self, key: &str) -> Option<&str> {
+        let lower_key = key.to_lowercase();
+        // 只是大小写写错了，直接给出建议
+        if let Some(known) = self.keys.iter().find(|k| k.to_lowercase() == lower_key) {
+            return Some(known);
+        }
+
+        let max_distance = std::cmp::max(2, key.chars().count() / 3);
+        self.keys
+            .iter()
+            .map(|known| (levenshtein(&lower_key, &known.to_lowercase()), known))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, known)| known.as_str())
+    }
+}
+
+/**
+ * 计算两个字符串的编辑距离
+ */
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+    let mut current = vec![0; b_chars.len() + 1];
+
+    for (i, a_char) in a.chars().enumerate() {
+        current[0] = i + 1;
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let cost = if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = (prev[j + 1] + 1).min(current[j] + 1).min(prev[j] + cost);
+        }
+        std::mem::swap(&mut prev, &mut current);
+    }
+
+    prev[b_chars.len()]
+}
@@ -0,0 +1,850 @@
+use crate::context::Context;
+use crate::handler::{Control, Handler, NodeKindMask};
+use crate::route::{
+    is_route_object, is_routes_array, prop_name, skip_non_route, static_string, NON_ROUTE_KINDS,
+};
+use deno_ast::swc::common::Spanned;
+use deno_ast::view::{self as ast_view, Node, NodeKind, NodeTrait};
+
+/**
+ * 类似 esquery 的选择器，用来在规则中描述需要检查的节点
+ *
+ * - `ObjectLit`、`*`：节点类型
+ * - `[key=children]`、`[value!='/']`、`[name^=use]`、`[text*=foo]`、`[key]`：节点的属性
+ * - `:route`、`:routes`：路由对象、路由数组
+ * - `A > B`、`A B`：子节点、后代节点
+ * - `A, B`：匹配任意一个
+ * - `@name`：捕获匹配的节点，例如 `ObjectLit:route@route > KeyValueProp[key=children]`
+ */
+#[derive(Clone, Debug)]
+pub struct Selector {
+    alternatives: Vec<Complex>,
+}
+
+/// 用组合符连接起来的一串简单选择器，匹配时从最右边开始
+#[derive(Clone, Debug)]
+struct Complex {
+    compounds: Vec<Compound>,
+    /// `combinators[i]` 连接 `compounds[i]` 和 `compounds[i + 1]`
+    combinators: Vec<Combinator>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Combinator {
+    Child,
+    Descendant,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Compound {
+    /// None 表示 `*`
+    kind: Option<NodeKind>,
+    attributes: Vec<Attribute>,
+    pseudos: Vec<Pseudo>,
+    capture: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct Attribute {
+    name: AttributeName,
+    /// None 表示只要求属性存在
+    test: Option<(Operator, String)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum AttributeName {
+    /// 属性的 key，包括简写属性
+    Key,
+    /// 标识符的名字
+    Name,
+    /// 字面量的值，属性上取的是属性值
+    Value,
+    /// 节点的源码
+    Text,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Operator {
+    Equal,
+    NotEqual,
+    Prefix,
+    Suffix,
+    Contains,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Pseudo {
+    Route,
+    Routes,
+}
+
+/// 匹配成功时通过 `@name` 捕获的节点
+#[derive(Clone, Default)]
+pub struct Captures<'a> {
+    nodes: Vec<(String, Node<'a>)>,
+}
+
+impl<'a> Captures<'a> {
+    pub fn get(&self, name: &str) -> Option<Node<'a>> {
+        self.nodes
+            .iter()
+            .find(|(capture, _)| capture == name)
+            .map(|(_, node)| *node)
+    }
+}
+
+impl Selector {
+    pub fn parse(source: &str) -> Result<Selector, String> {
+        let mut parser = Parser {
+            source,
+            chars: source.char_indices().peekable(),
+        };
+        let alternatives = parser.parse_list()?;
+        Ok(Selector { alternatives })
+    }
+
+    /// 能够被匹配到的节点类型，也就是最右边的选择器的类型
+    pub fn node_kinds(&self) -> NodeKindMask {
+        self.alternatives
+            .iter()
+            .fold(NodeKindMask::NONE, |mask, complex| {
+                match complex.compounds.last().and_then(|compound| compound.kind) {
+                    Some(kind) => mask.union(NodeKindMask::of(&[kind])),
+                    None => NodeKindMask::ALL,
+                }
+            })
+    }
+
+    /**
+     * 判断节点是否匹配，匹配时返回捕获的节点
+     */
+    pub fn matches<'a>(&self, node: Node<'a>, ctx: &Context) -> Option<Captures<'a>> {
+        self.alternatives.iter().find_map(|complex| {
+            let last = complex.compounds.len() - 1;
+            let mut captures = Captures::default();
+            if complex.compounds[last].matches(node, ctx)
+                && complex.matches_from(last, node, ctx, &mut captures)
+            {
+                complex.compounds[last].capture(node, &mut captures);
+                Some(captures)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+impl std::str::FromStr for Selector {
+    type Err = String;
+
+    fn from_str(source: &str) -> Result<Selector, String> {
+        Selector::parse(source)
+    }
+}
+
+impl Complex {
+    /// `node` 已经匹配了 `compounds[index]`，继续向上匹配左边的选择器
+    fn matches_from<'a>(
+        &self,
+        index: usize,
+        node: Node<'a>,
+        ctx: &Context,
+        captures: &mut Captures<'a>,
+    ) -> bool {
+        if index == 0 {
+            return true;
+        }
+        let compound = &self.compounds[index - 1];
+        let mut ancestor = node.parent();
+        while let Some(candidate) = ancestor {
+            if compound.matches(candidate, ctx) {
+                let len = captures.nodes.len();
+                if self.matches_from(index - 1, candidate, ctx, captures) {
+                    compound.capture(candidate, captures);
+                    return true;
+                }
+                captures.nodes.truncate(len);
+            }
+            if self.combinators[index - 1] == Combinator::Child {
+                break;
+            }
+            ancestor = candidate.parent();
+        }
+        false
+    }
+}
+
+impl Compound {
+    fn matches(&self, node: Node, ctx: &Context) -> bool {
+        if matches!(self.kind, Some(kind) if kind != node.kind()) {
+            return false;
+        }
+        self.attributes
+            .iter()
+            .all(|attribute| attribute.matches(node, ctx))
+            && self.pseudos.iter().all(|pseudo| match (pseudo, node) {
+                (Pseudo::Route, Node::ObjectLit(object_lit)) => is_route_object(object_lit),
+                (Pseudo::Routes, Node::ArrayLit(array_lit)) => is_routes_array(array_lit),
+                _ => false,
+            })
+    }
+
+    fn capture<'a>(&self, node: Node<'a>, captures: &mut Captures<'a>) {
+        if let Some(name) = &self.capture {
+            captures.nodes.push((name.clone(), node));
+        }
+    }
+}
+
+impl Attribute {
+    fn matches(&self, node: Node, ctx: &Context) -> bool {
+        let value = match self.name.value(node, ctx) {
+            Some(value) => value,
+            None => return false,
+        };
+        match &self.test {
+            None => true,
+            Some((Operator::Equal, expected)) => value == *expected,
+            Some((Operator::NotEqual, expected)) => value != *expected,
+            Some((Operator::Prefix, expected)) => value.starts_with(expected.as_str()),
+            Some((Operator::Suffix, expected)) => value.ends_with(expected.as_str()),
+            Some((Operator::Contains, expected)) => value.contains(expected.as_str()),
+        }
+    }
+}
+
+impl AttributeName {
+    fn value(&self, node: Node, ctx: &Context) -> Option<String> {
+        match self {
+            AttributeName::Key => match node {
+                Node::KeyValueProp(prop) => prop_name(&prop.key).map(|(key, _)| key),
+                Node::MethodProp(prop) => prop_name(&prop.key).map(|(key, _)| key),
+                Node::GetterProp(prop) => prop_name(&prop.key).map(|(key, _)| key),
+                Node::SetterProp(prop) => prop_name(&prop.key).map(|(key, _)| key),
+                Node::AssignProp(prop) => Some(prop.key.sym().to_string()),
+                // 简写属性 `{ routes }` 在对象中只有一个标识符
+                Node::Ident(ident) if matches!(node.parent(), Some(Node::ObjectLit(_))) => {
+                    Some(ident.sym().to_string())
+                }
+                _ => None,
+            },
+            AttributeName::Name => match node {
+                Node::Ident(ident) => Some(ident.sym().to_string()),
+                Node::BindingIdent(ident) => Some(ident.id.sym().to_string()),
+                _ => None,
+            },
+            AttributeName::Value => match node {
+                Node::KeyValueProp(prop) => literal_value(&prop.value),
+                Node::Str(str) => Some(str.value().to_string()),
+                Node::Bool(bool) => Some(bool.value().to_string()),
+                Node::Number(number) => Some(number.value().to_string()),
+                Node::Tpl(tpl) if tpl.exprs.is_empty() => Some(
+                    ctx.file_text_substring(&tpl.span())
+                        .trim_matches('`')
+                        .to_string(),
+                ),
+                _ => None,
+            },
+            AttributeName::Text => Some(ctx.file_text_substring(&node.span()).to_string()),
+        }
+    }
+}
+
+/// 字符串、布尔值和数字的值，其他表达式无法静态确定
+fn literal_value(expr: &ast_view::Expr) -> Option<String> {
+    match expr {
+        ast_view::Expr::Lit(ast_view::Lit::Bool(bool)) => Some(bool.value().to_string()),
+        ast_view::Expr::Lit(ast_view::Lit::Num(number)) => Some(number.value().to_string()),
+        expr => static_string(expr),
+    }
+}
+
+struct Parser<'s> {
+    source: &'s str,
+    chars: std::iter::Peekable<std::str::CharIndices<'s>>,
+}
+
+impl<'s> Parser<'s> {
+    fn error(&mut self, reason: &str) -> String {
+        let position = self
+            .chars
+            .peek()
+            .map_or(self.source.len(), |(index, _)| *index);
+        format!(
+            "选择器 `{}` 第 {} 个字符附近{}",
+            self.source,
+            position + 1,
+            reason
+        )
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.chars.peek().map(|(_, char)| *char)
+    }
+
+    fn skip_whitespace(&mut self) -> bool {
+        let mut skipped = false;
+        while matches!(self.peek(), Some(char) if char.is_whitespace()) {
+            self.chars.next();
+            skipped = true;
+        }
+        skipped
+    }
+
+    fn eat(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.chars.next();
+            return true;
+        }
+        false
+    }
+
+    fn parse_list(&mut self) -> Result<Vec<Complex>, String> {
+        let mut alternatives = vec![self.parse_complex()?];
+        while self.eat(',') {
+            alternatives.push(self.parse_complex()?);
+        }
+        if self.peek().is_some() {
+            return Err(self.error("有无法识别的字符"));
+        }
+        Ok(alternatives)
+    }
+
+    fn parse_complex(&mut self) -> Result<Complex, String> {
+        self.skip_whitespace();
+        let mut complex = Complex {
+            compounds: vec![self.parse_compound()?],
+            combinators: vec![],
+        };
+        loop {
+            let whitespace = self.skip_whitespace();
+            let combinator = match self.peek() {
+                Some('>') => {
+                    self.chars.next();
+                    self.skip_whitespace();
+                    Combinator::Child
+                }
+                Some(',') | None => break,
+                Some(_) if whitespace => Combinator::Descendant,
+                Some(_) => return Err(self.error("缺少组合符")),
+            };
+            complex.combinators.push(combinator);
+            complex.compounds.push(self.parse_compound()?);
+        }
+        Ok(complex)
+    }
+
+    fn parse_compound(&mut self) -> Result<Compound, String> {
+        let mut compound = Compound::default();
+        if self.eat('*') {
+        } else if matches!(self.peek(), Some(char) if char.is_ascii_alphabetic()) {
+            let name = self.parse_identifier();
+            compound.kind = Some(
+                node_kind(&name)
+                    .ok_or_else(|| self.error(&format!("有未知的节点类型 `{}`", name)))?,
+            );
+        } else if !matches!(self.peek(), Some('[') | Some(':')) {
+            return Err(self.error("缺少节点类型"));
+        }
+
+        loop {
+            match self.peek() {
+                Some('[') => {
+                    self.chars.next();
+                    compound.attributes.push(self.parse_attribute()?);
+                }
+                Some(':') => {
+                    self.chars.next();
+                    let pseudo = match self.parse_identifier().as_str() {
+                        "route" => Pseudo::Route,
+                        "routes" => Pseudo::Routes,
+                        name => return Err(self.error(&format!("有未知的伪类 `:{}`", name))),
+                    };
+                    compound.pseudos.push(pseudo);
+                }
+                Some('@') => {
+                    self.chars.next();
+                    let name = self.parse_identifier();
+                    if name.is_empty() {
+                        return Err(self.error("缺少捕获的名字"));
+                    }
+                    compound.capture = Some(name);
+                }
+                _ => return Ok(compound),
+            }
+        }
+    }
+
+    fn parse_attribute(&mut self) -> Result<Attribute, String> {
+        self.skip_whitespace();
+        let name = match self.parse_identifier().as_str() {
+            "key" => AttributeName::Key,
+            "name" => AttributeName::Name,
+            "value" => AttributeName::Value,
+            "text" => AttributeName::Text,
+            name => return Err(self.error(&format!("有未知的属性 `{}`", name))),
+        };
+        self.skip_whitespace();
+        if self.eat(']') {
+            return Ok(Attribute { name, test: None });
+        }
+
+        let operator = match self.chars.next().map(|(_, char)| char) {
+            Some('=') => Operator::Equal,
+            Some(char @ ('!' | '^' | '$' | '*')) if self.eat('=') => match char {
+                '!' => Operator::NotEqual,
+                '^' => Operator::Prefix,
+                '$' => Operator::Suffix,
+                _ => Operator::Contains,
+            },
+            _ => return Err(self.error("缺少比较运算符")),
+        };
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some(quote @ ('\'' | '"')) => {
+                self.chars.next();
+                let mut value = String::new();
+                loop {
+                    match self.chars.next() {
+                        Some((_, char)) if char == quote => break,
+                        Some((_, char)) => value.push(char),
+                        None => return Err(self.error("字符串没有结束")),
+                    }
+                }
+                value
+            }
+            _ => {
+                let mut value = String::new();
+                while let Some(char) = self.peek() {
+                    if char == ']' || char.is_whitespace() {
+                        break;
+                    }
+                    value.push(char);
+                    self.chars.next();
+                }
+                value
+            }
+        };
+        self.skip_whitespace();
+        if !self.eat(']') {
+            return Err(self.error("缺少 `]`"));
+        }
+        Ok(Attribute {
+            name,
+            test: Some((operator, value)),
+        })
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let mut identifier = String::new();
+        while let Some(char) = self.peek() {
+            if !(char.is_ascii_alphanumeric() || char == '_' || char == '-') {
+                break;
+            }
+            identifier.push(char);
+            self.chars.next();
+        }
+        identifier
+    }
+}
+
+/**
+ * 根据名字找到节点类型，名字和 `NodeKind` 的 Display 一致
+ */
+fn node_kind(name: &str) -> Option<NodeKind> {
+    use NodeKind::*;
+    const KINDS: &[NodeKind] = &[
+        ArrayLit,
+        ArrayPat,
+        ArrowExpr,
+        AssignExpr,
+        AssignPat,
+        AssignPatProp,
+        AssignProp,
+        AwaitExpr,
+        BigInt,
+        BinExpr,
+        BindingIdent,
+        BlockStmt,
+        Bool,
+        BreakStmt,
+        CallExpr,
+        CatchClause,
+        Class,
+        ClassDecl,
+        ClassExpr,
+        ClassMethod,
+        ClassProp,
+        ComputedPropName,
+        CondExpr,
+        Constructor,
+        ContinueStmt,
+        DebuggerStmt,
+        Decorator,
+        DoWhileStmt,
+        EmptyStmt,
+        ExportAll,
+        ExportDecl,
+        ExportDefaultDecl,
+        ExportDefaultExpr,
+        ExportDefaultSpecifier,
+        ExportNamedSpecifier,
+        ExportNamespaceSpecifier,
+        ExprOrSpread,
+        ExprStmt,
+        FnDecl,
+        FnExpr,
+        ForInStmt,
+        ForOfStmt,
+        ForStmt,
+        Function,
+        GetterProp,
+        Ident,
+        IfStmt,
+        ImportDecl,
+        ImportDefaultSpecifier,
+        ImportNamedSpecifier,
+        ImportStarAsSpecifier,
+        Invalid,
+        JSXAttr,
+        JSXClosingElement,
+        JSXClosingFragment,
+        JSXElement,
+        JSXEmptyExpr,
+        JSXExprContainer,
+        JSXFragment,
+        JSXMemberExpr,
+        JSXNamespacedName,
+        JSXOpeningElement,
+        JSXOpeningFragment,
+        JSXSpreadChild,
+        JSXText,
+        KeyValuePatProp,
+        KeyValueProp,
+        LabeledStmt,
+        MemberExpr,
+        MetaPropExpr,
+        MethodProp,
+        Module,
+        NamedExport,
+        NewExpr,
+        Null,
+        Number,
+        ObjectLit,
+        ObjectPat,
+        OptChainExpr,
+        Param,
+        ParenExpr,
+        PrivateMethod,
+        PrivateName,
+        PrivateProp,
+        Regex,
+        RestPat,
+        ReturnStmt,
+        Script,
+        SeqExpr,
+        SetterProp,
+        SpreadElement,
+        StaticBlock,
+        Str,
+        Super,
+        SwitchCase,
+        SwitchStmt,
+        TaggedTpl,
+        ThisExpr,
+        ThrowStmt,
+        Tpl,
+        TplElement,
+        TryStmt,
+        TsArrayType,
+        TsAsExpr,
+        TsCallSignatureDecl,
+        TsConditionalType,
+        TsConstAssertion,
+        TsConstructSignatureDecl,
+        TsConstructorType,
+        TsEnumDecl,
+        TsEnumMember,
+        TsExportAssignment,
+        TsExprWithTypeArgs,
+        TsExternalModuleRef,
+        TsFnType,
+        TsGetterSignature,
+        TsImportEqualsDecl,
+        TsImportType,
+        TsIndexSignature,
+        TsIndexedAccessType,
+        TsInferType,
+        TsInterfaceBody,
+        TsInterfaceDecl,
+        TsIntersectionType,
+        TsKeywordType,
+        TsLitType,
+        TsMappedType,
+        TsMethodSignature,
+        TsModuleBlock,
+        TsModuleDecl,
+        TsNamespaceDecl,
+        TsNamespaceExportDecl,
+        TsNonNullExpr,
+        TsOptionalType,
+        TsParamProp,
+        TsParenthesizedType,
+        TsPropertySignature,
+        TsQualifiedName,
+        TsRestType,
+        TsSetterSignature,
+        TsThisType,
+        TsTplLitType,
+        TsTupleElement,
+        TsTupleType,
+        TsTypeAliasDecl,
+        TsTypeAnn,
+        TsTypeAssertion,
+        TsTypeLit,
+        TsTypeOperator,
+        TsTypeParam,
+        TsTypeParamDecl,
+        TsTypeParamInstantiation,
+        TsTypePredicate,
+        TsTypeQuery,
+        TsTypeRef,
+        TsUnionType,
+        UnaryExpr,
+        UpdateExpr,
+        VarDecl,
+        VarDeclarator,
+        WhileStmt,
+        WithStmt,
+        YieldExpr,
+    ];
+    KINDS.iter().copied().find(|kind| kind.to_string() == name)
+}
+
+type Callback = Box<dyn for<'a> FnMut(Node<'a>, &Captures<'a>, &mut Context)>;
+
+/**
+ * 通过选择器订阅节点的 handler，规则的 `handler` 可以直接返回它
+ */
+#[derive(Default)]
+pub struct Query {
+    subscriptions: Vec<(Selector, Callback)>,
+    mask: NodeKindMask,
+    skip_non_route: bool,
+}
+
+impl Query {
+    pub fn new() -> Query {
+        Query::default()
+    }
+
+    /**
+     * 订阅选择器，选择器有语法错误时返回错误信息
+     */
+    pub fn on(
+        self,
+        selector: &str,
+        callback: impl for<'a> FnMut(Node<'a>, &Captures<'a>, &mut Context) + 'static,
+    ) -> Result<Query, String> {
+        let selector = Selector::parse(selector)?;
+        Ok(self.on_selector(selector, callback))
+    }
+
+    pub fn on_selector(
+        mut self,
+        selector: Selector,
+        callback: impl for<'a> FnMut(Node<'a>, &Captures<'a>, &mut Context) + 'static,
+    ) -> Query {
+        self.mask = self.mask.union(selector.node_kinds());
+        self.subscriptions.push((selector, Box::new(callback)));
+        self
+    }
+
+    /// 跳过不会包含路由配置的子树，参考 `route::skip_non_route`
+    pub fn skip_non_route(mut self) -> Query {
+        self.skip_non_route = true;
+        self
+    }
+}
+
+impl Handler for Query {
+    fn node_kinds(&self) -> NodeKindMask {
+        if self.skip_non_route {
+            self.mask.union(NON_ROUTE_KINDS)
+        } else {
+            self.mask
+        }
+    }
+
+    fn on_enter_node(&mut self, node: Node, ctx: &mut Context) -> Control {
+        for (selector, callback) in &mut self.subscriptions {
+            if let Some(captures) = selector.matches(node, ctx) {
+                callback(node, &captures, ctx);
+            }
+        }
+        if self.skip_non_route {
+            skip_non_route(node)
+        } else {
+            Control::Continue
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    /// 返回所有匹配的节点的源码，捕获的节点写成 `name=源码` 跟在后面
+    fn select(selector: &str, source: &str) -> Vec<String> {
+        let selector = Selector::parse(selector).unwrap();
+        let config = Config::default();
+        let parsed_source = crate::parse_program("routes.tsx", source.to_string()).unwrap();
+        parsed_source.with_view(|program| {
+            let ctx = Context::new(
+                "routes.tsx".to_string(),
+                parsed_source.media_type(),
+                &config,
+                parsed_source.source(),
+                program,
+            );
+            let mut matched = vec![];
+            let mut stack = vec![program.as_node()];
+            while let Some(node) = stack.pop() {
+                if let Some(captures) = selector.matches(node, &ctx) {
+                    let mut text = ctx.file_text_substring(&node.span()).to_string();
+                    for (name, node) in &captures.nodes {
+                        text.push_str(&format!(
+                            " {}={}",
+                            name,
+                            ctx.file_text_substring(&node.span())
+                        ));
+                    }
+                    matched.push(text);
+                }
+                stack.extend(node.children().into_iter().rev());
+            }
+            matched
+        })
+    }
+
+    fn parse_error(selector: &str) -> String {
+        Selector::parse(selector).unwrap_err()
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        assert_eq!(parse_error(""), "选择器 `` 第 1 个字符附近缺少节点类型");
+        assert_eq!(
+            parse_error("Foo"),
+            "选择器 `Foo` 第 4 个字符附近有未知的节点类型 `Foo`"
+        );
+        assert!(parse_error("ObjectLit[foo]").ends_with("有未知的属性 `foo`"));
+        assert!(parse_error("ObjectLit[key~=a]").ends_with("缺少比较运算符"));
+        assert!(parse_error("ObjectLit[key=a").ends_with("缺少 `]`"));
+        assert!(parse_error("ObjectLit[key='a]").ends_with("字符串没有结束"));
+        assert!(parse_error("ObjectLit:foo").ends_with("有未知的伪类 `:foo`"));
+        assert!(parse_error("ObjectLit@").ends_with("缺少捕获的名字"));
+        assert!(parse_error("ObjectLit,").ends_with("缺少节点类型"));
+        assert!(parse_error("ObjectLit >").ends_with("缺少节点类型"));
+        assert!(parse_error("ObjectLit)").ends_with("缺少组合符"));
+        assert!("Ident[name=a]".parse::<Selector>().is_ok());
+    }
+
+    #[test]
+    fn matches_child_and_descendant() {
+        let source = "const a = [{ b: { c: 1 } }];";
+        // 数组的元素外面还有一层 ExprOrSpread
+        assert!(select("ArrayLit > ObjectLit", source).is_empty());
+        assert_eq!(
+            select("ArrayLit > ExprOrSpread > ObjectLit", source),
+            ["{ b: { c: 1 } }"]
+        );
+        assert_eq!(
+            select("ArrayLit ObjectLit", source),
+            ["{ b: { c: 1 } }", "{ c: 1 }"]
+        );
+        // 中间隔着 KeyValueProp，不是直接的子节点
+        assert!(select("ObjectLit > ObjectLit", source).is_empty());
+        assert_eq!(
+            select("ObjectLit > KeyValueProp > ObjectLit", source),
+            ["{ c: 1 }"]
+        );
+        assert_eq!(
+            select("ArrayLit  >  * > KeyValueProp[key=c]", source).len(),
+            0
+        );
+        assert_eq!(select("ArrayLit * KeyValueProp[key=c]", source), ["c: 1"]);
+    }
+
+    #[test]
+    fn matches_attribute_operators() {
+        let source = "const a = { path: '/admin', name: 'users', useA: true, count: 1, routes };";
+        assert_eq!(
+            select("KeyValueProp[value='/admin']", source),
+            ["path: '/admin'"]
+        );
+        assert_eq!(
+            select("KeyValueProp[key!=path]", source),
+            ["name: 'users'", "useA: true", "count: 1"]
+        );
+        assert_eq!(select("KeyValueProp[key^=use]", source), ["useA: true"]);
+        assert_eq!(
+            select("KeyValueProp[value$=ers]", source),
+            ["name: 'users'"]
+        );
+        assert_eq!(
+            select("KeyValueProp[text*=\"min\"]", source),
+            ["path: '/admin'"]
+        );
+        assert_eq!(select("KeyValueProp[value=true]", source), ["useA: true"]);
+        assert_eq!(select("KeyValueProp[value=1]", source), ["count: 1"]);
+        // 简写属性只有一个标识符
+        assert_eq!(select("Ident[key=routes]", source), ["routes"]);
+        assert_eq!(select("Ident[name=a]", source), ["a"]);
+        assert_eq!(select("Str[value]", source), ["'/admin'", "'users'"]);
+    }
+
+    #[test]
+    fn matches_route_pseudos() {
+        let source = "export default [{ path: '/a' }];\nconst b = [{ path: '/b' }];";
+        assert_eq!(select("ObjectLit:route", source), ["{ path: '/a' }"]);
+        assert_eq!(select("ArrayLit:routes", source), ["[{ path: '/a' }]"]);
+    }
+
+    #[test]
+    fn captures_nodes() {
+        let source = "export default [{ path: '/a', children: [] }];";
+        assert_eq!(
+            select("ArrayLit@list ObjectLit:route@route > KeyValueProp[key=children]", source),
+            ["children: [] list=[{ path: '/a', children: [] }] route={ path: '/a', children: [] }"]
+        );
+        assert_eq!(
+            select("KeyValueProp[key=path]@prop", source),
+            ["path: '/a' prop=path: '/a'"]
+        );
+    }
+
+    #[test]
+    fn matches_any_of_a_list() {
+        let source = "const a = { b: 1, c: 'c', d };";
+        assert_eq!(
+            select("KeyValueProp[key=b], Ident[key=d] ,Str", source),
+            ["b: 1", "'c'", "d"]
+        );
+        let selector = Selector::parse("KeyValueProp, Str").unwrap();
+        assert!(selector.node_kinds().contains(NodeKind::KeyValueProp));
+        assert!(selector.node_kinds().contains(NodeKind::Str));
+        assert!(!selector.node_kinds().contains(NodeKind::Ident));
+        assert!(Selector::parse("Str, *")
+            .unwrap()
+            .node_kinds()
+            .contains(NodeKind::Ident));
+    }
+
+    #[test]
+    fn query_reports_invalid_selectors() {
+        assert!(Query::new().on("ObjectLit[", |_, _, _| {}).is_err());
+        assert!(Query::new().on("ObjectLit", |_, _, _| {}).is_ok());
+    }
+}
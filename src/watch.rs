@@ -0,0 +1,185 @@
+use route_lint::{Config, FixMode, Job, Linter, CONFIG_FILE_NAME};
+
+use notify::RecursiveMode;
+use notify_debouncer_mini::new_debouncer;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// 保存文件时编辑器可能会触发多次写入，合并这段时间内的修改
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// 路由中引用的文件，相对于项目的 src 目录
+const REFERENCED_FILES: &[&str] = &["access.ts", "access.js"];
+const REFERENCED_DIRS: &[&str] = &["locales", "pages"];
+
+/// 需要监听的文件和目录
+#[derive(Default)]
+struct WatchSet {
+    /// 只关心这些文件本身，监听的是它们所在的目录
+    files: Vec<PathBuf>,
+    /// 目录中任何文件变化都需要重新检查
+    dirs: Vec<PathBuf>,
+}
+
+impl WatchSet {
+    fn contains(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file == path)
+            || self.dirs.iter().any(|dir| path.starts_with(dir))
+    }
+
+    /// 实际交给 watcher 的路径，文件监听所在的目录，避免编辑器替换文件之后监听失效
+    fn targets(&self) -> Vec<(PathBuf, RecursiveMode)> {
+        let mut targets: Vec<(PathBuf, RecursiveMode)> = vec![];
+        for dir in self.files.iter().filter_map(|file| file.parent()) {
+            if !targets.iter().any(|(target, _)| target == dir) {
+                targets.push((dir.to_path_buf(), RecursiveMode::NonRecursive));
+            }
+        }
+        for dir in &self.dirs {
+            targets.push((dir.clone(), RecursiveMode::Recursive));
+        }
+        targets
+    }
+}
+
+/**
+ * 检查一次并持续监听相关文件，文件变化时清空终端重新检查
+ */
+pub fn run(
+    path: PathBuf,
+    config_path: Option<PathBuf>,
+    fix: bool,
+    jobs: usize,
+) -> Result<(), String> {
+    let (sender, receiver) = mpsc::channel();
+    let mut debouncer =
+        new_debouncer(DEBOUNCE, sender).map_err(|err| format!("监听文件异常： {}", err))?;
+    let mut watching: Vec<(PathBuf, RecursiveMode)> = vec![];
+
+    loop {
+        let files = redraw(&path, config_path.clone(), fix, jobs);
+        let watch_set = watch_set(&path, &files, config_path.as_deref());
+
+        // 路由文件 import 的文件可能发生变化，每次检查之后更新监听的路径
+        let targets = watch_set.targets();
+        for (target, _) in watching.iter().filter(|watched| !targets.contains(watched)) {
+            let _ = debouncer.watcher().unwatch(target);
+        }
+        for (target, mode) in targets.iter().filter(|target| !watching.contains(target)) {
+            if let Err(err) = debouncer.watcher().watch(target, *mode) {
+                eprintln!("⚠️ 无法监听 `{}`: {}", target.display(), err);
+            }
+        }
+        watching = targets;
+
+        // 等到相关的文件发生变化
+        loop {
+            match receiver.recv() {
+                Ok(Ok(events)) if events.iter().any(|event| watch_set.contains(&event.path)) => {
+                    break
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(err)) => eprintln!("⚠️ 监听文件异常： {}", err),
+                Err(_) => return Ok(()),
+            }
+        }
+    }
+}
+
+/**
+ * 清空终端并重新检查，返回检查过的路由文件
+ */
+fn redraw(path: &Path, config_path: Option<PathBuf>, fix: bool, jobs: usize) -> Vec<PathBuf> {
+    print!("\x1B[2J\x1B[3J\x1B[H");
+
+    // 监听过程中的错误只需要展示出来，修改之后会重新检查
+    let result = crate::load_config(config_path, path).map(|config| {
+        let linter = Linter::new(config);
+        let entry = Job {
+            path: path.to_path_buf(),
+            content: None,
+            linter: &linter,
+        };
+        let fix = if fix { FixMode::Write } else { FixMode::Off };
+        crate::lint_entries(vec![entry], fix, jobs)
+    });
+    let files = match result {
+        Ok(summary) => {
+            if let Some(err) = summary.failure {
+                eprintln!("{}", err.0);
+            } else if summary.total == 0 {
+                println!("👍 没有发现任何问题，非常好!");
+            }
+            // import 进来的文件解析失败时也要监听，修复之后才能重新检查
+            summary.files
+        }
+        Err(err) => {
+            eprintln!("{}", err.0);
+            vec![path.to_path_buf()]
+        }
+    };
+    println!("\n👀 正在监听 `{}` 的变化，按 Ctrl+C 退出", path.display());
+    files
+}
+
+/**
+ * 路由文件、import 的路由文件、配置文件，以及项目中 access、locales、pages 的变化都需要重新检查
+ */
+fn watch_set(path: &Path, files: &[PathBuf], config_path: Option<&Path>) -> WatchSet {
+    let mut watch_set = WatchSet::default();
+    let mut add_file = |file: &Path| {
+        // notify 返回的是绝对路径
+        if let Ok(file) = std::fs::canonicalize(file) {
+            if !watch_set.files.contains(&file) {
+                watch_set.files.push(file);
+            }
+        }
+    };
+
+    for file in files {
+        add_file(file);
+    }
+    match config_path {
+        Some(config_path) => add_file(config_path),
+        None => {
+            if let Some(config_path) = Config::discover(path) {
+                add_file(&config_path);
+            }
+        }
+    }
+
+    if let Some(src) = project_root(path).map(|root| root.join("src")) {
+        for file in REFERENCED_FILES {
+            add_file(&src.join(file));
+        }
+        for dir in REFERENCED_DIRS {
+            if let Ok(dir) = std::fs::canonicalize(src.join(dir)) {
+                watch_set.dirs.push(dir);
+            }
+        }
+    }
+
+    // 还没有配置文件时，新建的配置文件也需要生效
+    if config_path.is_none() {
+        if let Some(dir) = watch_set.files.first().and_then(|file| file.parent()) {
+            let file = dir.join(CONFIG_FILE_NAME);
+            if !watch_set.files.contains(&file) {
+                watch_set.files.push(file);
+            }
+        }
+    }
+
+    watch_set
+}
+
+/**
+ * 从路由文件所在目录向上查找 package.json 所在的目录
+ */
+fn project_root(path: &Path) -> Option<PathBuf> {
+    let path = std::fs::canonicalize(path).ok()?;
+    path.ancestors()
+        .skip(1)
+        .find(|dir| dir.join("package.json").is_file())
+        .map(Path::to_path_buf)
+}